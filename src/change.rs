@@ -1,14 +1,17 @@
 use std::{
+    borrow::Cow,
     error::Error,
     fmt::Display,
+    ops::Range,
     path::{Path, PathBuf},
 };
 
-use crate::{BuildVersioningError, ChangeType, PackageName, Versioning};
+use crate::{BuildVersioningError, ChangeFile, PackageName, Versioning};
 
 /// Represents a single [change](https://github.com/knope-dev/changesets#terminology) which is
 /// applicable to any number of packages.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Change {
     /// Something to uniquely identify a change.
     ///
@@ -45,13 +48,7 @@ impl Change {
     /// - If the file doesn't have a valid front matter
     /// - If the file doesn't have valid versioning info in the front matter
     pub fn from_file<T: AsRef<Path>>(path: T) -> Result<Self, LoadingError> {
-        let path = path.as_ref();
-        let file_name = path
-            .file_name()
-            .ok_or(LoadingError::InvalidFileName)?
-            .to_string_lossy();
-        let contents = std::fs::read_to_string(path)?;
-        Self::from_file_name_and_content(file_name.as_ref(), &contents)
+        ParseOptions::default().load_file(path)
     }
 
     /// Given the name of a file and its content, create a [`Change`].
@@ -65,45 +62,27 @@ impl Change {
         file_name: &str,
         content: &str,
     ) -> Result<Self, LoadingError> {
-        let unique_id = file_name
-            .strip_suffix(".md")
-            .ok_or(LoadingError::InvalidFileName)
-            .map(UniqueId::exact)?;
-        Self::from_str(unique_id, content).map_err(LoadingError::from)
+        ParseOptions::default().parse(file_name, content)
     }
 
+    #[cfg(test)]
     fn from_str(unique_id: UniqueId, content: &str) -> Result<Self, ParsingError> {
-        let mut lines = content.lines();
-        let first_line = lines.next().ok_or(ParsingError::MissingFrontMatter)?;
-        if first_line.trim() != "---" {
-            return Err(ParsingError::MissingFrontMatter);
-        }
-        let versioning_iter = lines
-            .clone()
-            .take_while(|line| line.trim() != "---")
-            .map(|line| {
-                let parts = line
-                    .split_once(':')
-                    .ok_or(ParsingError::InvalidFrontMatter)?;
-                let package_name = PackageName::from(parts.0.trim());
-                let change_type = ChangeType::from(parts.1.trim());
-                Ok((package_name, change_type))
-            })
-            .collect::<Result<Vec<(String, ChangeType)>, ParsingError>>()?;
-        let versioning = Versioning::try_from_iter(versioning_iter)?;
-        let mut lines = lines.skip(versioning.len());
-        let end_front_matter = lines.next().ok_or(ParsingError::InvalidFrontMatter)?;
-        if end_front_matter.trim() != "---" {
-            return Err(ParsingError::InvalidFrontMatter);
-        }
-        let summary = lines
-            .skip_while(|line| line.trim().is_empty())
-            .collect::<Vec<_>>()
-            .join("\n");
-        Ok(Self {
-            unique_id,
-            versioning,
-            summary,
+        Self::parse(unique_id, content, &ParseOptions::default())
+    }
+
+    fn parse(
+        unique_id: UniqueId,
+        content: &str,
+        options: &ParseOptions,
+    ) -> Result<Self, ParsingError> {
+        let file = ChangeFile::parse(content, options)?;
+        file.to_change(unique_id).map_err(|err| {
+            ParsingError::new(
+                ParsingErrorKind::InvalidVersioning(err),
+                file.closing_index,
+                file.closing_line(),
+                0,
+            )
         })
     }
 }
@@ -111,6 +90,7 @@ impl Change {
 #[cfg(test)]
 mod test_change {
     use super::*;
+    use crate::ChangeType;
 
     #[test]
     fn it_can_contain_spaces_in_package_names() {
@@ -160,6 +140,161 @@ This is a summary
         );
     }
 
+    #[test]
+    fn it_can_contain_quoted_package_names() {
+        let change = Change::from_str(
+            UniqueId::normalize("a change"),
+            r#"---
+"@scope/package": minor
+'it''s: quoted' : "major"
+"escaped \"\u0041\"": 'custom: type'
+---
+This is a summary
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            change.versioning,
+            Versioning::from_iter(vec![
+                (PackageName::from("@scope/package"), ChangeType::Minor),
+                (PackageName::from("it's: quoted"), ChangeType::Major),
+                (
+                    PackageName::from("escaped \"A\""),
+                    ChangeType::Custom("custom: type".into())
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn it_rejects_invalid_quotes() {
+        for line in [
+            r#""unterminated: patch"#,
+            r#""package" patch"#,
+            r#""package": "patch" extra"#,
+            r#""bad \escape": patch"#,
+        ] {
+            assert!(
+                Change::from_str(
+                    UniqueId::normalize("a change"),
+                    &format!("---\n{line}\n---\nSummary")
+                )
+                .is_err(),
+                "{line}"
+            );
+        }
+    }
+
+    #[test]
+    fn it_quotes_package_names_when_needed() {
+        let change = Change {
+            unique_id: UniqueId::exact("a_change"),
+            versioning: Versioning::from(("@scope/package", ChangeType::Custom("a: b".into()))),
+            summary: "Summary".into(),
+        };
+        let written = change.to_string();
+        assert_eq!(
+            written,
+            "---\n\"@scope/package\": \"a: b\"\n---\n\nSummary\n"
+        );
+        assert_eq!(
+            Change::from_str(UniqueId::exact("a_change"), &written).unwrap(),
+            change
+        );
+
+        let change = Change {
+            unique_id: UniqueId::exact("a_change"),
+            versioning: Versioning::from(("plain-package_name", ChangeType::Patch)),
+            summary: "Summary".into(),
+        };
+        assert_eq!(
+            change.to_string(),
+            "---\nplain-package_name: patch\n---\n\nSummary\n"
+        );
+    }
+
+    #[test]
+    fn it_reports_where_errors_are() {
+        let err = Change::from_str(
+            UniqueId::exact("a_change"),
+            "---\nmy_package: patch\nother package minor\n---\n",
+        )
+        .unwrap_err();
+        assert_eq!(err.kind, ParsingErrorKind::InvalidFrontMatter);
+        assert_eq!((err.line, err.column), (3, 20));
+        assert_eq!(err.text, "other package minor");
+        assert_eq!(
+            err.to_string(),
+            "line 3:20: invalid front matter, expected `package: change type`"
+        );
+
+        let mut err = Change::from_str(
+            UniqueId::exact("a_change"),
+            "---\n\"my_package\": \"bad \\q\"\n---\n",
+        )
+        .unwrap_err();
+        assert_eq!((err.line, err.column), (2, 20));
+        err.path = Some(PathBuf::from(".changeset/a_change.md"));
+        assert_eq!(
+            err.snippet(),
+            r#"error: invalid front matter, expected `package: change type`
+ --> .changeset/a_change.md:2:20
+  |
+2 | "my_package": "bad \q"
+  |                    ^
+"#
+        );
+    }
+
+    #[test]
+    fn it_reports_front_matter_errors() {
+        let err = Change::from_str(UniqueId::exact("a_change"), "# Just a summary").unwrap_err();
+        assert_eq!(err.kind, ParsingErrorKind::MissingFrontMatter);
+        assert_eq!((err.line, err.column), (1, 1));
+
+        let err =
+            Change::from_str(UniqueId::exact("a_change"), "---\nmy_package: patch\n").unwrap_err();
+        assert_eq!(err.kind, ParsingErrorKind::UnterminatedFrontMatter);
+        assert_eq!((err.line, err.column), (1, 1));
+
+        let err = Change::from_str(UniqueId::exact("a_change"), "---\n---\n").unwrap_err();
+        assert_eq!(
+            err.kind,
+            ParsingErrorKind::InvalidVersioning(BuildVersioningError::EmptyVersioningError)
+        );
+        assert_eq!((err.line, err.column), (2, 1));
+    }
+
+    #[test]
+    fn it_rejects_duplicate_packages() {
+        let content = "---\nknope: patch\nother: minor\n  knope: major\n---\n";
+        let err = Change::from_str(UniqueId::exact("a_change"), content).unwrap_err();
+        assert_eq!(
+            err.kind,
+            ParsingErrorKind::DuplicatePackage {
+                package_name: "knope".to_string(),
+                first_line: 2
+            }
+        );
+        assert_eq!((err.line, err.column), (4, 3));
+        assert_eq!(
+            err.to_string(),
+            "line 4:3: `knope` is listed more than once (first on line 2)"
+        );
+
+        let options = ParseOptions {
+            duplicates: DuplicatePackages::MergeToHighest,
+        };
+        let change = options.parse("a_change.md", content).unwrap();
+        assert_eq!(
+            change.versioning.into_iter().collect::<Vec<_>>(),
+            vec![
+                ("knope".to_string(), ChangeType::Major),
+                ("other".to_string(), ChangeType::Minor)
+            ]
+        );
+    }
+
     #[test]
     fn it_can_have_an_empty_summary() {
         let change = Change::from_str(
@@ -173,11 +308,130 @@ package: patch
     }
 }
 
+/// Parse a `package: change type` line, where either side may be a YAML-style quoted string
+/// (like `"@scope/package": minor`).
+///
+/// On success, also returns where the change type (including any quotes) is in `line`. On failure,
+/// returns the byte offset in `line` where the problem was found.
+pub(crate) fn parse_front_matter_line(
+    line: &str,
+) -> Result<(PackageName, String, Range<usize>), usize> {
+    let offset_of = |rest: &str| line.len() - rest.len();
+    let trimmed = line.trim_start();
+    let (package_name, rest) = if trimmed.starts_with(['"', '\'']) {
+        let (package_name, rest) =
+            parse_quoted(trimmed).map_err(|offset| offset_of(trimmed) + offset)?;
+        let rest = rest.trim_start();
+        let rest = rest.strip_prefix(':').ok_or_else(|| offset_of(rest))?;
+        (package_name, rest)
+    } else {
+        let (package_name, rest) = trimmed.split_once(':').ok_or(line.len())?;
+        (package_name.trim().to_string(), rest)
+    };
+    let rest = rest.trim_start();
+    let start = offset_of(rest);
+    let (change_type, end) = if rest.starts_with(['"', '\'']) {
+        let (change_type, after) = parse_quoted(rest).map_err(|offset| start + offset)?;
+        let end = offset_of(after);
+        let after = after.trim_start();
+        if !after.is_empty() {
+            return Err(offset_of(after));
+        }
+        (change_type, end)
+    } else {
+        let value = rest.trim_end();
+        (value.to_string(), start + value.len())
+    };
+    Ok((package_name, change_type, start..end))
+}
+
+/// Parse a single- or double-quoted string at the start of `text`, returning the unescaped value
+/// and the rest of the text after the closing quote.
+///
+/// On failure, returns the byte offset in `text` where the problem was found.
+fn parse_quoted(text: &str) -> Result<(String, &str), usize> {
+    let mut chars = text.char_indices();
+    let Some((_, quote)) = chars.next() else {
+        return Err(0);
+    };
+    let mut value = String::new();
+    while let Some((index, c)) = chars.next() {
+        let after = text.get(index + 1..).unwrap_or_default();
+        match (quote, c) {
+            ('\'', '\'') => {
+                // In single quotes, a quote is escaped by doubling it
+                if after.starts_with('\'') {
+                    chars.next();
+                    value.push('\'');
+                } else {
+                    return Ok((value, after));
+                }
+            }
+            ('"', '"') => return Ok((value, after)),
+            ('"', '\\') => {
+                let escaped = match chars.next() {
+                    Some((_, 'n')) => '\n',
+                    Some((_, 't')) => '\t',
+                    Some((_, 'r')) => '\r',
+                    Some((_, '0')) => '\0',
+                    Some((_, 'u')) => {
+                        let hex: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+                        u32::from_str_radix(&hex, 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                            .ok_or(index)?
+                    }
+                    Some((_, escaped @ ('"' | '\\' | '/'))) => escaped,
+                    _ => return Err(index),
+                };
+                value.push(escaped);
+            }
+            (_, c) => value.push(c),
+        }
+    }
+    // The quote was never closed
+    Err(0)
+}
+
+/// Quote `value` (as a YAML double-quoted string) if it can't be written as-is in front matter.
+pub(crate) fn quote_if_needed(value: &str) -> Cow<'_, str> {
+    let needs_quotes = value.is_empty()
+        || value.trim() != value
+        || value.starts_with([
+            '@', '`', '\'', '"', '!', '&', '*', '|', '>', '%', '{', '}', '[', ']', ',', '#', '?',
+            '-',
+        ])
+        || value.contains([':', '#', '\\', '\n', '\r', '\t']);
+    if !needs_quotes {
+        return Cow::Borrowed(value);
+    }
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    Cow::Owned(quoted)
+}
+
 impl Display for Change {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "---")?;
         for (package_name, change_type) in self.versioning.iter() {
-            writeln!(f, "{package_name}: {change_type}")?;
+            let change_type = change_type.to_string();
+            writeln!(
+                f,
+                "{}: {}",
+                quote_if_needed(package_name),
+                quote_if_needed(&change_type)
+            )?;
         }
         writeln!(f, "---")?;
         writeln!(f)?;
@@ -187,6 +441,8 @@ impl Display for Change {
 
 /// The unique ID of a [`Change`], used to set the file name of the Markdown file.
 #[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct UniqueId(String);
 
 impl UniqueId {
@@ -263,33 +519,202 @@ mod test_unique_id_normalize {
     }
 }
 
+/// Options for how change files are parsed, for when the defaults of [`Change::from_file`] are too
+/// strict.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ParseOptions {
+    pub duplicates: DuplicatePackages,
+}
+
+impl ParseOptions {
+    /// Load a [`Change`] from a Markdown file, like [`Change::from_file`].
+    ///
+    /// # Errors
+    ///
+    /// - If the file can't be read
+    /// - If the file doesn't have a valid name (it doesn't end in `.md`)
+    /// - If the file doesn't have a valid front matter
+    /// - If the file doesn't have valid versioning info in the front matter
+    pub fn load_file<T: AsRef<Path>>(&self, path: T) -> Result<Change, LoadingError> {
+        let path = path.as_ref();
+        let file_name = path
+            .file_name()
+            .ok_or(LoadingError::InvalidFileName)?
+            .to_string_lossy();
+        let contents = std::fs::read_to_string(path)?;
+        self.parse(file_name.as_ref(), &contents)
+            .map_err(|err| match err {
+                LoadingError::Parsing(mut err) => {
+                    err.path = Some(path.to_path_buf());
+                    LoadingError::Parsing(err)
+                }
+                err => err,
+            })
+    }
+
+    /// Given the name of a file and its content, create a [`Change`], like
+    /// [`Change::from_file_name_and_content`].
+    ///
+    /// # Errors
+    ///
+    /// - If the file doesn't have a valid name (it doesn't end in `.md`)
+    /// - If the file doesn't have a valid front matter
+    /// - If the file doesn't have valid versioning info in the front matter
+    pub fn parse(&self, file_name: &str, content: &str) -> Result<Change, LoadingError> {
+        let unique_id = file_name
+            .strip_suffix(".md")
+            .ok_or(LoadingError::InvalidFileName)
+            .map(UniqueId::exact)?;
+        Change::parse(unique_id, content, self).map_err(LoadingError::from)
+    }
+}
+
+/// What to do when a change file lists the same package more than once.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum DuplicatePackages {
+    /// Fail with [`ParsingErrorKind::DuplicatePackage`].
+    #[default]
+    Error,
+    /// Keep the package once (where it was first listed) with the highest of its
+    /// [`crate::ChangeType`]s.
+    /// This is useful for legacy files which were accepted before duplicates were detected.
+    MergeToHighest,
+}
+
+/// The error that occurs when the contents of a change file are invalid, along with where the
+/// problem is.
 #[derive(Debug)]
-pub enum ParsingError {
-    MissingFrontMatter,
-    InvalidFrontMatter,
-    InvalidVersioning(BuildVersioningError),
+pub struct ParsingError {
+    pub kind: ParsingErrorKind,
+    /// The file that was being parsed, if it was loaded from a file.
+    pub path: Option<PathBuf>,
+    /// The line containing the problem, starting at 1.
+    pub line: usize,
+    /// The column (in characters) of the problem, starting at 1.
+    pub column: usize,
+    /// The full text of the line containing the problem.
+    pub text: String,
 }
 
-impl From<BuildVersioningError> for ParsingError {
-    fn from(err: BuildVersioningError) -> Self {
-        ParsingError::InvalidVersioning(err)
+impl ParsingError {
+    pub(crate) fn new(
+        kind: ParsingErrorKind,
+        line_index: usize,
+        text: &str,
+        offset: usize,
+    ) -> Self {
+        Self {
+            kind,
+            path: None,
+            line: line_index + 1,
+            column: text.get(..offset).unwrap_or(text).chars().count() + 1,
+            text: text.to_string(),
+        }
+    }
+
+    /// Render this error like a compiler diagnostic, with the offending line and a caret pointing
+    /// at the problem:
+    ///
+    /// ```text
+    /// error: invalid front matter, expected `package: change type`
+    ///  --> .changeset/a_change.md:2:12
+    ///   |
+    /// 2 | my_package patch
+    ///   |            ^
+    /// ```
+    #[must_use]
+    pub fn snippet(&self) -> String {
+        let gutter = " ".repeat(self.line.to_string().len());
+        let indent: String = self
+            .text
+            .chars()
+            .take(self.column.saturating_sub(1))
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let location = match &self.path {
+            Some(path) => format!("{}:{}:{}", path.display(), self.line, self.column),
+            None => format!("{}:{}", self.line, self.column),
+        };
+        format!(
+            "error: {kind}\n{gutter}--> {location}\n{gutter} |\n{line} | {text}\n{gutter} | {indent}^\n",
+            kind = self.kind,
+            line = self.line,
+            text = self.text,
+        )
     }
 }
 
 impl Display for ParsingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(path) = &self.path {
+            write!(f, "{}:", path.display())?;
+        } else {
+            f.write_str("line ")?;
+        }
+        write!(f, "{}:{}: {}", self.line, self.column, self.kind)
+    }
+}
+
+impl Error for ParsingError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.kind {
+            ParsingErrorKind::InvalidVersioning(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// The kind of problem found in a change file, see [`ParsingError`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParsingErrorKind {
+    /// The file doesn't start with `---`.
+    MissingFrontMatter,
+    /// The front matter was never closed with a second `---`.
+    UnterminatedFrontMatter,
+    /// A line in the front matter isn't a valid `package: change type` pair.
+    InvalidFrontMatter,
+    InvalidVersioning(BuildVersioningError),
+    /// The same package was listed more than once, see [`DuplicatePackages`]. The error points at
+    /// the second listing.
+    DuplicatePackage {
+        package_name: PackageName,
+        /// The line the package was first listed on, starting at 1.
+        first_line: usize,
+    },
+}
+
+impl Display for ParsingErrorKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            ParsingError::MissingFrontMatter => write!(f, "missing front matter"),
-            ParsingError::InvalidFrontMatter => write!(f, "invalid front matter"),
-            ParsingError::InvalidVersioning(err) => {
+            ParsingErrorKind::MissingFrontMatter => {
+                write!(f, "missing front matter, expected `---`")
+            }
+            ParsingErrorKind::UnterminatedFrontMatter => {
+                write!(f, "front matter is never closed with `---`")
+            }
+            ParsingErrorKind::InvalidFrontMatter => {
+                write!(f, "invalid front matter, expected `package: change type`")
+            }
+            ParsingErrorKind::InvalidVersioning(err) => {
                 write!(f, "invalid front matter: {err}")
             }
+            ParsingErrorKind::DuplicatePackage {
+                package_name,
+                first_line,
+            } => {
+                write!(
+                    f,
+                    "`{package_name}` is listed more than once (first on line {first_line})"
+                )
+            }
         }
     }
 }
 
-impl Error for ParsingError {}
-
 #[derive(Debug)]
 pub enum LoadingError {
     InvalidFileName,
@@ -0,0 +1,393 @@
+use std::{
+    fmt::{Display, Formatter},
+    ops::Range,
+    path::Path,
+    str::FromStr,
+};
+
+use crate::{
+    BuildVersioningError, Change, ChangeType, DuplicatePackages, LoadingError, PackageName,
+    ParseOptions, ParsingError, ParsingErrorKind, UniqueId, Versioning,
+    change::{parse_front_matter_line, quote_if_needed},
+};
+
+/// The text of a change file which can be edited without losing its formatting.
+///
+/// Unlike writing a [`Change`] (which regenerates the whole file), displaying a [`ChangeFile`]
+/// reproduces the original text exactly, including package order, blank lines and `#` comments in
+/// the front matter, line endings, and trailing whitespace. Edits only touch the lines they need
+/// to.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ChangeFile {
+    /// The opening `---` line.
+    opening: String,
+    front_matter: Vec<FrontMatterLine>,
+    /// The closing `---` line.
+    closing: String,
+    /// The index of the closing line in the file, for error messages.
+    pub(crate) closing_index: usize,
+    /// Blank lines between the front matter and the summary.
+    separator: String,
+    summary: String,
+    /// The line ending used by the file, for inserting new lines.
+    newline: &'static str,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum FrontMatterLine {
+    Package {
+        package_name: PackageName,
+        change_type: ChangeType,
+        /// The whole line, including its line ending.
+        text: String,
+        /// Where the change type is in `text`, including any quotes.
+        value: Range<usize>,
+    },
+    /// A blank line or comment.
+    Other(String),
+}
+
+impl ChangeFile {
+    /// Load a change file for editing.
+    ///
+    /// # Errors
+    ///
+    /// - If the file can't be read
+    /// - If the file doesn't have a valid front matter
+    pub fn from_file<T: AsRef<Path>>(path: T) -> Result<Self, LoadingError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)?;
+        Self::parse(&contents, &ParseOptions::default()).map_err(|mut err| {
+            err.path = Some(path.to_path_buf());
+            LoadingError::Parsing(err)
+        })
+    }
+
+    /// Parse the contents of a change file.
+    ///
+    /// If `options` allows duplicate packages, every line is kept and the package is treated as
+    /// having the highest of its change types.
+    ///
+    /// An empty front matter is allowed here (so it can be edited), but not by
+    /// [`ChangeFile::to_change`].
+    ///
+    /// # Errors
+    ///
+    /// If the file doesn't have a valid front matter.
+    pub fn parse(content: &str, options: &ParseOptions) -> Result<Self, ParsingError> {
+        let newline = if content.contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        };
+        let mut lines = content.split_inclusive('\n').enumerate();
+        let opening = lines.next().map_or("", |(_, line)| line);
+        if opening.trim() != "---" {
+            return Err(ParsingError::new(
+                ParsingErrorKind::MissingFrontMatter,
+                0,
+                without_newline(opening),
+                0,
+            ));
+        }
+        let mut front_matter = Vec::new();
+        // The index of the line each package was first listed on
+        let mut first_lines: Vec<(PackageName, usize)> = Vec::new();
+        let mut closing = None;
+        for (index, line) in lines.by_ref() {
+            let trimmed = line.trim();
+            if trimmed == "---" {
+                closing = Some((index, line));
+                break;
+            }
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                front_matter.push(FrontMatterLine::Other(line.to_string()));
+                continue;
+            }
+            let text = without_newline(line);
+            let (package_name, change_type, value) =
+                parse_front_matter_line(text).map_err(|offset| {
+                    ParsingError::new(ParsingErrorKind::InvalidFrontMatter, index, text, offset)
+                })?;
+            if let Some((_, first_index)) = first_lines
+                .iter()
+                .find(|(existing, _)| *existing == package_name)
+            {
+                if options.duplicates == DuplicatePackages::Error {
+                    return Err(ParsingError::new(
+                        ParsingErrorKind::DuplicatePackage {
+                            package_name,
+                            first_line: first_index + 1,
+                        },
+                        index,
+                        text,
+                        text.len() - text.trim_start().len(),
+                    ));
+                }
+            } else {
+                first_lines.push((package_name.clone(), index));
+            }
+            front_matter.push(FrontMatterLine::Package {
+                package_name,
+                change_type: ChangeType::from(change_type),
+                text: line.to_string(),
+                value,
+            });
+        }
+        let Some((closing_index, closing)) = closing else {
+            return Err(ParsingError::new(
+                ParsingErrorKind::UnterminatedFrontMatter,
+                0,
+                without_newline(opening),
+                0,
+            ));
+        };
+        let mut separator = String::new();
+        let mut summary = String::new();
+        for (_, line) in lines {
+            if summary.is_empty() && line.trim().is_empty() {
+                separator.push_str(line);
+            } else {
+                summary.push_str(line);
+            }
+        }
+        Ok(Self {
+            opening: opening.to_string(),
+            front_matter,
+            closing: closing.to_string(),
+            closing_index,
+            separator,
+            summary,
+            newline,
+        })
+    }
+
+    /// Every package in the front matter (in the order they're first listed) with its
+    /// [`ChangeType`].
+    #[must_use]
+    pub fn packages(&self) -> Vec<(&PackageName, &ChangeType)> {
+        let mut packages: Vec<(&PackageName, &ChangeType)> = Vec::new();
+        for line in &self.front_matter {
+            let FrontMatterLine::Package {
+                package_name,
+                change_type,
+                ..
+            } = line
+            else {
+                continue;
+            };
+            if let Some((_, existing)) = packages
+                .iter_mut()
+                .find(|(existing, _)| *existing == package_name)
+            {
+                if change_type > *existing {
+                    *existing = change_type;
+                }
+            } else {
+                packages.push((package_name, change_type));
+            }
+        }
+        packages
+    }
+
+    /// The [`ChangeType`] of `package_name`, if it's in the front matter.
+    #[must_use]
+    pub fn change_type(&self, package_name: &str) -> Option<&ChangeType> {
+        self.packages()
+            .into_iter()
+            .find(|(existing, _)| *existing == package_name)
+            .map(|(_, change_type)| change_type)
+    }
+
+    /// Set the [`ChangeType`] of `package_name`.
+    ///
+    /// If the package is already listed, only the change type on its line is replaced. Otherwise,
+    /// a new line is added after the last package.
+    pub fn set_change_type(&mut self, package_name: &str, change_type: ChangeType) {
+        let new_value = quote_if_needed(&change_type.to_string()).into_owned();
+        let mut found = false;
+        for line in &mut self.front_matter {
+            if let FrontMatterLine::Package {
+                package_name: existing,
+                change_type: existing_type,
+                text,
+                value,
+            } = line
+            {
+                if existing != package_name {
+                    continue;
+                }
+                text.replace_range(value.clone(), &new_value);
+                *value = value.start..value.start + new_value.len();
+                *existing_type = change_type.clone();
+                found = true;
+            }
+        }
+        if found {
+            return;
+        }
+        let key = format!("{}: ", quote_if_needed(package_name));
+        let value = key.len()..key.len() + new_value.len();
+        let line = FrontMatterLine::Package {
+            package_name: package_name.to_string(),
+            change_type,
+            text: format!("{key}{new_value}{}", self.newline),
+            value,
+        };
+        let position = self
+            .front_matter
+            .iter()
+            .rposition(|line| line.package_name().is_some())
+            .map_or(0, |index| index + 1);
+        self.front_matter.insert(position, line);
+    }
+
+    /// Remove every line for `package_name`, returning its [`ChangeType`] if it was listed.
+    pub fn remove_package(&mut self, package_name: &str) -> Option<ChangeType> {
+        let change_type = self.change_type(package_name).cloned();
+        self.front_matter
+            .retain(|line| line.package_name().is_none_or(|name| name != package_name));
+        change_type
+    }
+
+    /// The summary of the change, without the blank lines around it.
+    #[must_use]
+    pub fn summary(&self) -> &str {
+        self.summary.trim_end_matches(['\r', '\n'])
+    }
+
+    /// Replace the summary, keeping the blank lines which separate it from the front matter.
+    pub fn set_summary(&mut self, summary: &str) {
+        if self.separator.is_empty() {
+            self.separator = self.newline.to_string();
+        }
+        self.summary = summary
+            .lines()
+            .flat_map(|line| [line, self.newline])
+            .collect();
+    }
+
+    /// Convert to a [`Change`] with the given ID.
+    ///
+    /// # Errors
+    ///
+    /// If there are no packages in the front matter.
+    pub fn to_change(&self, unique_id: UniqueId) -> Result<Change, BuildVersioningError> {
+        let versioning = Versioning::try_from_iter(
+            self.packages()
+                .into_iter()
+                .map(|(package_name, change_type)| (package_name.clone(), change_type.clone())),
+        )?;
+        Ok(Change {
+            unique_id,
+            versioning,
+            summary: self.change_summary(),
+        })
+    }
+
+    /// The summary as it appears in [`Change::summary`], with `\n` line endings.
+    pub(crate) fn change_summary(&self) -> String {
+        self.summary.lines().collect::<Vec<_>>().join("\n")
+    }
+
+    /// The closing `---` line, without its line ending.
+    pub(crate) fn closing_line(&self) -> &str {
+        without_newline(&self.closing)
+    }
+}
+
+impl FrontMatterLine {
+    fn package_name(&self) -> Option<&PackageName> {
+        match self {
+            Self::Package { package_name, .. } => Some(package_name),
+            Self::Other(_) => None,
+        }
+    }
+}
+
+impl FromStr for ChangeFile {
+    type Err = ParsingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, &ParseOptions::default())
+    }
+}
+
+impl Display for ChangeFile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.opening)?;
+        for line in &self.front_matter {
+            match line {
+                FrontMatterLine::Package { text, .. } | FrontMatterLine::Other(text) => {
+                    f.write_str(text)?;
+                }
+            }
+        }
+        f.write_str(&self.closing)?;
+        f.write_str(&self.separator)?;
+        f.write_str(&self.summary)
+    }
+}
+
+#[cfg(feature = "serde")]
+serde_as_string!(ChangeFile);
+
+fn without_newline(line: &str) -> &str {
+    let line = line.strip_suffix('\n').unwrap_or(line);
+    line.strip_suffix('\r').unwrap_or(line)
+}
+
+#[cfg(test)]
+mod test_change_file {
+    use super::*;
+
+    const CONTENT: &str = "---\r\n# Packages\r\nknope:   patch  \r\n\r\n\"@scope/b\": minor\r\n---\r\n\r\n\r\n# A change\r\n\r\nDetails  \r\n";
+
+    #[test]
+    fn it_round_trips() {
+        let file = ChangeFile::from_str(CONTENT).unwrap();
+        assert_eq!(file.to_string(), CONTENT);
+        assert_eq!(
+            file.packages(),
+            vec![
+                (&"knope".to_string(), &ChangeType::Patch),
+                (&"@scope/b".to_string(), &ChangeType::Minor)
+            ]
+        );
+        assert_eq!(file.summary(), "# A change\r\n\r\nDetails  ");
+    }
+
+    #[test]
+    fn it_only_changes_edited_lines() {
+        let mut file = ChangeFile::from_str(CONTENT).unwrap();
+        file.set_change_type("knope", ChangeType::Major);
+        file.set_change_type("new: package", ChangeType::Custom("security".into()));
+        assert_eq!(file.remove_package("@scope/b"), Some(ChangeType::Minor));
+        assert_eq!(
+            file.to_string(),
+            "---\r\n# Packages\r\nknope:   major  \r\n\r\n\"new: package\": security\r\n---\r\n\r\n\r\n# A change\r\n\r\nDetails  \r\n"
+        );
+
+        file.set_summary("Something else\nentirely");
+        assert!(
+            file.to_string()
+                .ends_with("---\r\n\r\n\r\nSomething else\r\nentirely\r\n")
+        );
+        let change = file.to_change(UniqueId::exact("a_change")).unwrap();
+        assert_eq!(change.summary, "Something else\nentirely");
+        assert_eq!(change.versioning.len(), 2);
+    }
+
+    #[test]
+    fn it_merges_duplicates_when_allowed() {
+        let content = "---\nknope: minor\nknope: patch\n---\n";
+        assert!(ChangeFile::from_str(content).is_err());
+        let options = ParseOptions {
+            duplicates: DuplicatePackages::MergeToHighest,
+        };
+        let mut file = ChangeFile::parse(content, &options).unwrap();
+        assert_eq!(file.change_type("knope"), Some(&ChangeType::Minor));
+        file.set_change_type("knope", ChangeType::Major);
+        assert_eq!(file.to_string(), "---\nknope: major\nknope: major\n---\n");
+    }
+}
@@ -0,0 +1,834 @@
+use std::{
+    error::Error,
+    fmt::{Display, Formatter},
+    path::Path,
+};
+
+use crate::{ChangeType, PackageChange, Release, Version};
+
+/// Controls how a [`Release`] is rendered as a section of a Markdown changelog.
+///
+/// The rendered section looks like this (which is the format [Knope] uses):
+///
+/// ```markdown
+/// ## 1.5.0 (2025-03-08)
+///
+/// ### Features
+///
+/// - A change with a single-line summary
+///
+/// #### A change with a multi-line summary
+///
+/// The first line is promoted to a heading, the rest is the body.
+/// ```
+///
+/// [Knope]: https://github.com/knope-dev/knope
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChangelogFormat {
+    /// The sections of the changelog, in the order they should appear.
+    ///
+    /// Each change is placed in the first section which lists its [`ChangeType`]. Any
+    /// [`ChangeType::Custom`] without a section is placed in a section named after its label,
+    /// after all the configured sections.
+    pub sections: Vec<ChangelogSection>,
+}
+
+impl Default for ChangelogFormat {
+    fn default() -> Self {
+        Self {
+            sections: vec![
+                ChangelogSection::new("Breaking Changes", [ChangeType::Major]),
+                ChangelogSection::new("Features", [ChangeType::Minor]),
+                ChangelogSection::new("Fixes", [ChangeType::Patch]),
+                ChangelogSection::new("Notes", [ChangeType::None]),
+            ],
+        }
+    }
+}
+
+impl ChangelogFormat {
+    /// Render the changes of a [`Release`] as a Markdown section for `version`, released on `date`.
+    ///
+    /// The returned string always ends in a single newline. Empty sections are omitted.
+    #[must_use]
+    pub fn render(&self, release: &Release, version: &Version, date: &str) -> String {
+        let mut sections: Vec<(&str, Vec<&PackageChange>)> = self
+            .sections
+            .iter()
+            .map(|section| (section.heading.as_str(), Vec::new()))
+            .collect();
+        for change in &release.changes {
+            let index = self
+                .sections
+                .iter()
+                .position(|section| section.change_types.contains(&change.change_type));
+            if let Some((_, changes)) = index.and_then(|index| sections.get_mut(index)) {
+                changes.push(change);
+                continue;
+            }
+            let heading = match &change.change_type {
+                ChangeType::Custom(label) => label.as_str(),
+                ChangeType::None => "none",
+                ChangeType::Patch => "patch",
+                ChangeType::Minor => "minor",
+                ChangeType::Major => "major",
+            };
+            if let Some((_, changes)) = sections.iter_mut().find(|(name, _)| *name == heading) {
+                changes.push(change);
+            } else {
+                sections.push((heading, vec![change]));
+            }
+        }
+
+        Rendered {
+            version,
+            date,
+            sections,
+        }
+        .to_string()
+    }
+
+    /// The [`ChangeType`] of changes listed under a section `heading`, the reverse of
+    /// [`ChangelogFormat::render`].
+    ///
+    /// Headings are matched case-insensitively. Unknown headings are [`ChangeType::Custom`].
+    #[must_use]
+    pub fn change_type_for(&self, heading: &str) -> ChangeType {
+        self.sections
+            .iter()
+            .find(|section| section.heading.eq_ignore_ascii_case(heading))
+            .and_then(|section| section.change_types.first())
+            .cloned()
+            .unwrap_or_else(|| ChangeType::from(heading))
+    }
+}
+
+/// A [`Release`] whose changes have been sorted into sections, ready to be written.
+struct Rendered<'a> {
+    version: &'a Version,
+    date: &'a str,
+    sections: Vec<(&'a str, Vec<&'a PackageChange>)>,
+}
+
+impl Display for Rendered<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "## {} ({})", self.version, self.date)?;
+        for (heading, changes) in &self.sections {
+            if changes.is_empty() {
+                continue;
+            }
+            writeln!(f)?;
+            writeln!(f, "### {heading}")?;
+            let (simple, complex): (Vec<_>, Vec<_>) = changes
+                .iter()
+                .map(|change| Entry::from(change.summary.as_ref()))
+                .partition(|entry| entry.body.is_none());
+            if !simple.is_empty() {
+                writeln!(f)?;
+            }
+            for entry in simple {
+                writeln!(f, "- {}", entry.title)?;
+            }
+            for entry in complex {
+                writeln!(f)?;
+                writeln!(f, "#### {}", entry.title)?;
+                if let Some(body) = entry.body {
+                    writeln!(f)?;
+                    writeln!(f, "{body}")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A heading in a changelog and the [`ChangeType`]s that belong under it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChangelogSection {
+    /// The text of the heading, without any leading `#`.
+    pub heading: String,
+    pub change_types: Vec<ChangeType>,
+}
+
+impl ChangelogSection {
+    #[must_use]
+    pub fn new<Heading, ChangeTypes>(heading: Heading, change_types: ChangeTypes) -> Self
+    where
+        Heading: Into<String>,
+        ChangeTypes: IntoIterator<Item = ChangeType>,
+    {
+        Self {
+            heading: heading.into(),
+            change_types: change_types.into_iter().collect(),
+        }
+    }
+}
+
+/// A single summary split into the parts that are rendered.
+struct Entry<'summary> {
+    title: &'summary str,
+    body: Option<&'summary str>,
+}
+
+impl<'summary> From<&'summary str> for Entry<'summary> {
+    fn from(summary: &'summary str) -> Self {
+        let summary = summary.trim();
+        let (title, body) = summary.split_once('\n').unwrap_or((summary, ""));
+        // Only strip a Markdown heading, not something like `#123` at the start of the title
+        let title = parse_heading(title).map_or(title.trim(), |(_, text)| text);
+        let body = body.trim();
+        Self {
+            title,
+            body: (!body.is_empty()).then_some(body),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_changelog_format {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::UniqueId;
+
+    fn change(id: &str, change_type: ChangeType, summary: &str) -> PackageChange {
+        PackageChange {
+            unique_id: Arc::new(UniqueId::exact(id)),
+            change_type,
+            summary: summary.into(),
+            provenance: None,
+        }
+    }
+
+    #[test]
+    fn it_renders_sections_in_order() {
+        let release = Release {
+            package_name: "my_package".into(),
+            changes: vec![
+                change("a", ChangeType::Patch, "A fix"),
+                change(
+                    "b",
+                    ChangeType::Major,
+                    "# A breaking change\n\nWith some details\n\nAcross paragraphs",
+                ),
+                change("c", ChangeType::Major, "### Another breaking change"),
+                change("d", ChangeType::Minor, "A feature"),
+            ],
+            minimum_change_type: None,
+        };
+        assert_eq!(
+            ChangelogFormat::default().render(&release, &Version::new(2, 0, 0), "2025-03-08"),
+            "## 2.0.0 (2025-03-08)
+
+### Breaking Changes
+
+- Another breaking change
+
+#### A breaking change
+
+With some details
+
+Across paragraphs
+
+### Features
+
+- A feature
+
+### Fixes
+
+- A fix
+"
+        );
+    }
+
+    #[test]
+    fn it_renders_custom_change_types() {
+        let release = Release {
+            package_name: "my_package".into(),
+            changes: vec![
+                change("a", ChangeType::Custom("note".into()), "A note"),
+                change("b", ChangeType::Custom("security".into()), "A security fix"),
+                change("c", ChangeType::Patch, "A fix"),
+            ],
+            minimum_change_type: None,
+        };
+        let mut format = ChangelogFormat::default();
+        format.sections.insert(
+            0,
+            ChangelogSection::new("Security", [ChangeType::Custom("security".into())]),
+        );
+        assert_eq!(
+            format.render(&release, &Version::new(1, 0, 1), "2025-03-08"),
+            "## 1.0.1 (2025-03-08)
+
+### Security
+
+- A security fix
+
+### Fixes
+
+- A fix
+
+### note
+
+- A note
+"
+        );
+    }
+
+    #[test]
+    fn it_only_strips_heading_markers() {
+        let release = Release {
+            package_name: "my_package".into(),
+            changes: vec![
+                change("a", ChangeType::Patch, "#123 fixed a crash"),
+                change("b", ChangeType::Patch, "## A fix with a heading"),
+            ],
+            minimum_change_type: None,
+        };
+        assert_eq!(
+            ChangelogFormat::default().render(&release, &Version::new(1, 0, 1), "2025-03-08"),
+            "## 1.0.1 (2025-03-08)
+
+### Fixes
+
+- #123 fixed a crash
+- A fix with a heading
+"
+        );
+    }
+}
+
+/// An existing Markdown changelog, like a `CHANGELOG.md` file.
+///
+/// Only the headings which start a version section (like `## 1.5.0 (2025-03-08)`) are
+/// interpreted, everything else is preserved exactly as written.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Changelog {
+    content: String,
+}
+
+impl Changelog {
+    /// Read a changelog from a file.
+    ///
+    /// # Errors
+    ///
+    /// If the file cannot be read.
+    pub fn from_file<T: AsRef<Path>>(path: T) -> std::io::Result<Self> {
+        std::fs::read_to_string(path).map(Self::from)
+    }
+
+    /// Write the (possibly modified) changelog back to a file.
+    ///
+    /// # Errors
+    ///
+    /// If the file cannot be written.
+    pub fn write_to_file<T: AsRef<Path>>(&self, path: T) -> std::io::Result<()> {
+        std::fs::write(path, &self.content)
+    }
+
+    /// Every version which already has a section in this changelog, in the order they appear
+    /// (usually newest first).
+    pub fn versions(&self) -> impl Iterator<Item = Version> + '_ {
+        self.version_headings().map(|heading| heading.version)
+    }
+
+    /// Parse every version section of this changelog into [`ReleaseNotes`], in the order they
+    /// appear.
+    ///
+    /// Headings one level below a version heading (like `### Features`) start a
+    /// [`ReleaseNotesSection`], whose [`ChangeType`] is determined by the matching
+    /// [`ChangelogSection`] in `format`. Headings which aren't in `format` are treated as
+    /// [`ChangeType::Custom`] labels, mirroring [`ChangelogFormat::render`]. Each bullet point and
+    /// each deeper heading (like `####`) within a section is a [`ChangelogEntry`].
+    #[must_use]
+    pub fn releases(&self, format: &ChangelogFormat) -> Vec<ReleaseNotes> {
+        let headings: Vec<VersionHeading> = self.version_headings().collect();
+        headings
+            .iter()
+            .enumerate()
+            .map(|(index, heading)| {
+                let end = headings
+                    .get(index + 1)
+                    .map_or(self.content.len(), |next| next.offset);
+                let body = self
+                    .content
+                    .get(heading.offset..end)
+                    .unwrap_or_default()
+                    .split_once('\n')
+                    .map_or("", |(_, body)| body);
+                ReleaseNotes {
+                    version: heading.version.clone(),
+                    date: heading.date.clone(),
+                    sections: parse_sections(body, heading.level, format),
+                }
+            })
+            .collect()
+    }
+
+    /// Insert a section (usually created by [`ChangelogFormat::render`]) for `version` above the
+    /// most recent version section, after any header or preamble.
+    ///
+    /// If there are no version sections yet, the new section is added to the end.
+    ///
+    /// # Errors
+    ///
+    /// If the changelog already has a section for `version`, nothing is changed and
+    /// [`InsertError::VersionAlreadyExists`] is returned.
+    pub fn insert(&mut self, version: &Version, section: &str) -> Result<(), InsertError> {
+        if self.versions().any(|existing| existing == *version) {
+            return Err(InsertError::VersionAlreadyExists(version.clone()));
+        }
+        let section = section.trim_end();
+        let first_version = self.version_headings().next().map(|heading| heading.offset);
+        if let Some(offset) = first_version {
+            let preamble = self.content.get(..offset).unwrap_or_default();
+            let separator = if preamble.is_empty() || preamble.ends_with("\n\n") {
+                ""
+            } else {
+                "\n"
+            };
+            self.content
+                .insert_str(offset, &format!("{separator}{section}\n\n"));
+        } else {
+            let preamble = self.content.trim_end();
+            self.content = if preamble.is_empty() {
+                format!("{section}\n")
+            } else {
+                format!("{preamble}\n\n{section}\n")
+            };
+        }
+        Ok(())
+    }
+
+    /// Every heading which starts a version section.
+    ///
+    /// The first heading which contains a version determines which heading level is used for
+    /// versions, headings inside of fenced code blocks are ignored.
+    fn version_headings(&self) -> impl Iterator<Item = VersionHeading> + '_ {
+        let mut in_code_block = false;
+        let mut version_level = None;
+        let mut offset = 0;
+        self.content.split_inclusive('\n').filter_map(move |line| {
+            let line_offset = offset;
+            offset += line.len();
+            let line = line.trim();
+            if is_fence(line) {
+                in_code_block = !in_code_block;
+            }
+            if in_code_block {
+                return None;
+            }
+            let heading = VersionHeading::parse(line, line_offset)?;
+            (*version_level.get_or_insert(heading.level) == heading.level).then_some(heading)
+        })
+    }
+}
+
+impl From<String> for Changelog {
+    fn from(content: String) -> Self {
+        Self { content }
+    }
+}
+
+impl From<&str> for Changelog {
+    fn from(content: &str) -> Self {
+        Self::from(content.to_string())
+    }
+}
+
+impl Display for Changelog {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.content)
+    }
+}
+
+/// A heading like `## 1.5.0 (2025-03-08)`, `## [1.5.0] - 2025-03-08`, or `# v1.5.0`.
+struct VersionHeading {
+    /// The byte offset of the start of the heading in the changelog.
+    offset: usize,
+    /// The number of `#` in the heading.
+    level: usize,
+    version: Version,
+    date: Option<String>,
+}
+
+impl VersionHeading {
+    fn parse(line: &str, offset: usize) -> Option<Self> {
+        let (level, text) = parse_heading(line)?;
+        let text = text.strip_prefix('[').unwrap_or(text);
+        let text = text.strip_prefix('v').unwrap_or(text);
+        let end = text
+            .find(|c: char| c.is_whitespace() || c == ']')
+            .unwrap_or(text.len());
+        let version = text.get(..end)?.parse().ok()?;
+        let date = text
+            .get(end..)
+            .unwrap_or_default()
+            .trim_start_matches(|c: char| c.is_whitespace() || c == ']' || c == '-')
+            .trim_start_matches('(')
+            .trim_end_matches(')')
+            .trim();
+        Some(Self {
+            offset,
+            level,
+            version,
+            date: (!date.is_empty()).then(|| date.to_string()),
+        })
+    }
+}
+
+/// Split a Markdown heading into its level and text.
+fn parse_heading(line: &str) -> Option<(usize, &str)> {
+    let text = line.trim_start_matches('#');
+    let level = line.len() - text.len();
+    if level == 0 || !text.starts_with(' ') {
+        return None;
+    }
+    Some((level, text.trim()))
+}
+
+fn is_fence(line: &str) -> bool {
+    line.starts_with("```") || line.starts_with("~~~")
+}
+
+/// Parse the contents of a single version section (excluding the version heading itself).
+fn parse_sections(
+    body: &str,
+    version_level: usize,
+    format: &ChangelogFormat,
+) -> Vec<ReleaseNotesSection> {
+    let mut sections: Vec<ReleaseNotesSection> = Vec::new();
+    let mut entry: Option<ChangelogEntry> = None;
+    let mut in_code_block = false;
+    for line in body.lines() {
+        if is_fence(line.trim()) {
+            in_code_block = !in_code_block;
+        }
+        let heading = if in_code_block {
+            None
+        } else {
+            parse_heading(line)
+        };
+        let bullet = line.strip_prefix("- ").or_else(|| line.strip_prefix("* "));
+        match (heading, bullet, &mut entry) {
+            (Some((level, text)), _, _) if level == version_level + 1 => {
+                push_entry(&mut sections, entry.take());
+                sections.push(ReleaseNotesSection {
+                    heading: Some(text.to_string()),
+                    change_type: Some(format.change_type_for(text)),
+                    entries: Vec::new(),
+                });
+            }
+            (Some((level, text)), _, _) if level > version_level + 1 => {
+                push_entry(&mut sections, entry.take());
+                entry = Some(ChangelogEntry {
+                    title: text.to_string(),
+                    body: Some(String::new()),
+                });
+            }
+            (
+                _,
+                _,
+                Some(ChangelogEntry {
+                    body: Some(body), ..
+                }),
+            ) => {
+                body.push_str(line);
+                body.push('\n');
+            }
+            (_, Some(text), _) => {
+                push_entry(&mut sections, entry.take());
+                entry = Some(ChangelogEntry {
+                    title: text.trim().to_string(),
+                    body: None,
+                });
+            }
+            (_, None, Some(ChangelogEntry { title, body: None }))
+                if line.starts_with(char::is_whitespace) && !line.trim().is_empty() =>
+            {
+                title.push(' ');
+                title.push_str(line.trim());
+            }
+            _ => {}
+        }
+    }
+    push_entry(&mut sections, entry);
+    sections
+}
+
+/// Add a completed entry to the last section, creating a section without a heading if needed.
+fn push_entry(sections: &mut Vec<ReleaseNotesSection>, entry: Option<ChangelogEntry>) {
+    let Some(mut entry) = entry else {
+        return;
+    };
+    entry.body = entry
+        .body
+        .map(|body| body.trim().to_string())
+        .filter(|body| !body.is_empty());
+    if sections.is_empty() {
+        sections.push(ReleaseNotesSection {
+            heading: None,
+            change_type: None,
+            entries: Vec::new(),
+        });
+    }
+    if let Some(section) = sections.last_mut() {
+        section.entries.push(entry);
+    }
+}
+
+/// A single version section parsed from a [`Changelog`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReleaseNotes {
+    pub version: Version,
+    /// Whatever comes after the version in the heading, without any surrounding parentheses.
+    pub date: Option<String>,
+    pub sections: Vec<ReleaseNotesSection>,
+}
+
+/// A group of entries under a heading (like `### Features`) in [`ReleaseNotes`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReleaseNotesSection {
+    /// The text of the heading, or `None` for entries which came before any section heading.
+    pub heading: Option<String>,
+    /// The [`ChangeType`] this heading represents, see [`ChangelogFormat::change_type_for`].
+    pub change_type: Option<ChangeType>,
+    pub entries: Vec<ChangelogEntry>,
+}
+
+/// A single documented change in [`ReleaseNotes`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChangelogEntry {
+    /// The text of a bullet point, or the text of a heading.
+    pub title: String,
+    /// The content below the heading, for entries which had one.
+    pub body: Option<String>,
+}
+
+impl ChangelogEntry {
+    /// This entry as the summary of a [`crate::Change`], which [`ChangelogFormat::render`] would
+    /// turn back into this entry.
+    #[must_use]
+    pub fn summary(&self) -> String {
+        match &self.body {
+            Some(body) => format!("# {}\n\n{body}", self.title),
+            None => self.title.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_changelog {
+    use super::*;
+
+    const SECTION: &str = "## 1.1.0 (2025-03-09)\n\n### Features\n\n- A feature\n";
+
+    #[test]
+    fn it_inserts_after_the_preamble() {
+        let mut changelog = Changelog::from(
+            "# Changelog\n\nSome hand-written notes.\n\n## 1.0.0 (2025-03-08)\n\n### Fixes\n\n- A fix\n",
+        );
+        changelog.insert(&Version::new(1, 1, 0), SECTION).unwrap();
+        assert_eq!(
+            changelog.to_string(),
+            "# Changelog\n\nSome hand-written notes.\n\n## 1.1.0 (2025-03-09)\n\n### Features\n\n- A feature\n\n## 1.0.0 (2025-03-08)\n\n### Fixes\n\n- A fix\n"
+        );
+    }
+
+    #[test]
+    fn it_inserts_without_a_preamble() {
+        let mut changelog = Changelog::from("## 1.0.0 (2025-03-08)\n\n- A fix\n");
+        changelog.insert(&Version::new(1, 1, 0), SECTION).unwrap();
+        assert_eq!(
+            changelog.to_string(),
+            "## 1.1.0 (2025-03-09)\n\n### Features\n\n- A feature\n\n## 1.0.0 (2025-03-08)\n\n- A fix\n"
+        );
+    }
+
+    #[test]
+    fn it_appends_when_there_are_no_versions() {
+        let mut changelog = Changelog::from("# Changelog\n");
+        changelog.insert(&Version::new(1, 1, 0), SECTION).unwrap();
+        assert_eq!(
+            changelog.to_string(),
+            "# Changelog\n\n## 1.1.0 (2025-03-09)\n\n### Features\n\n- A feature\n"
+        );
+
+        let mut changelog = Changelog::default();
+        changelog.insert(&Version::new(1, 1, 0), SECTION).unwrap();
+        assert_eq!(changelog.to_string(), SECTION);
+    }
+
+    #[test]
+    fn it_ignores_code_blocks_and_other_heading_levels() {
+        let content = "# Changelog\n\n```markdown\n## 0.1.0\n```\n\n## [v1.0.0] - 2025-03-08\n\n### 0.9.0 is mentioned here\n";
+        let changelog = Changelog::from(content);
+        assert_eq!(
+            changelog.versions().collect::<Vec<_>>(),
+            vec![Version::new(1, 0, 0)]
+        );
+    }
+
+    #[test]
+    fn it_refuses_duplicate_versions() {
+        let content = "# Changelog\n\n## 1.1.0 (2025-03-08)\n\n- A fix\n";
+        let mut changelog = Changelog::from(content);
+        assert_eq!(
+            changelog.insert(&Version::new(1, 1, 0), SECTION),
+            Err(InsertError::VersionAlreadyExists(Version::new(1, 1, 0)))
+        );
+        assert_eq!(changelog.to_string(), content);
+    }
+
+    #[test]
+    fn it_parses_releases() {
+        let changelog = Changelog::from(
+            "## 0.4.0 (2025-03-08)
+
+### Breaking Changes
+
+- Update edition to 2024 and MSRV to 1.85
+
+#### Stop normalizing paths for existing files
+
+If you already have a change file,
+potentially created by another tool.
+
+## 0.3.0 (2024-05-05)
+
+- A fix without a section
+",
+        );
+        let releases = changelog.releases(&ChangelogFormat::default());
+        assert_eq!(
+            releases,
+            vec![
+                ReleaseNotes {
+                    version: Version::new(0, 4, 0),
+                    date: Some("2025-03-08".into()),
+                    sections: vec![ReleaseNotesSection {
+                        heading: Some("Breaking Changes".into()),
+                        change_type: Some(ChangeType::Major),
+                        entries: vec![
+                            ChangelogEntry {
+                                title: "Update edition to 2024 and MSRV to 1.85".into(),
+                                body: None,
+                            },
+                            ChangelogEntry {
+                                title: "Stop normalizing paths for existing files".into(),
+                                body: Some(
+                                    "If you already have a change file,\npotentially created by another tool.".into()
+                                ),
+                            },
+                        ],
+                    }],
+                },
+                ReleaseNotes {
+                    version: Version::new(0, 3, 0),
+                    date: Some("2024-05-05".into()),
+                    sections: vec![ReleaseNotesSection {
+                        heading: None,
+                        change_type: None,
+                        entries: vec![ChangelogEntry {
+                            title: "A fix without a section".into(),
+                            body: None,
+                        }],
+                    }],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn it_round_trips_rendered_releases() {
+        let format = ChangelogFormat::default();
+        let section = "## 1.1.0 (2025-03-09)
+
+### Features
+
+- A feature
+  that wraps
+
+#### A complex feature
+
+With details
+
+```markdown
+### Not a section
+```
+
+### security
+
+- A security fix
+";
+        let releases = Changelog::from(section).releases(&format);
+        let release = releases.first().unwrap();
+        assert_eq!(
+            release.sections,
+            vec![
+                ReleaseNotesSection {
+                    heading: Some("Features".into()),
+                    change_type: Some(ChangeType::Minor),
+                    entries: vec![
+                        ChangelogEntry {
+                            title: "A feature that wraps".into(),
+                            body: None,
+                        },
+                        ChangelogEntry {
+                            title: "A complex feature".into(),
+                            body: Some(
+                                "With details\n\n```markdown\n### Not a section\n```".into()
+                            ),
+                        },
+                    ],
+                },
+                ReleaseNotesSection {
+                    heading: Some("security".into()),
+                    change_type: Some(ChangeType::Custom("security".into())),
+                    entries: vec![ChangelogEntry {
+                        title: "A security fix".into(),
+                        body: None,
+                    }],
+                },
+            ]
+        );
+        assert_eq!(
+            release
+                .sections
+                .first()
+                .unwrap()
+                .entries
+                .get(1)
+                .unwrap()
+                .summary(),
+            "# A complex feature\n\nWith details\n\n```markdown\n### Not a section\n```"
+        );
+    }
+}
+
+/// The error that occurs when a section can't be inserted into a [`Changelog`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum InsertError {
+    /// The changelog already contains a section for this version.
+    VersionAlreadyExists(Version),
+}
+
+impl Display for InsertError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::VersionAlreadyExists(version) => {
+                write!(f, "the changelog already contains version {version}")
+            }
+        }
+    }
+}
+
+impl Error for InsertError {}
@@ -1,42 +1,305 @@
-use std::{path::Path, sync::Arc};
+use std::{
+    cmp::Ordering,
+    collections::BTreeMap,
+    error::Error,
+    fmt::{Display, Formatter},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
+};
 
 use crate::{
-    Change, ChangeType, PackageName,
+    Bump, BumpRules, Change, ChangeType, PackageName, ParseOptions, Provenance, Version,
     change::{LoadingError, UniqueId},
 };
 
 /// A set of [`Change`]s that combine to form [`Release`]s of one or more packages.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ChangeSet {
-    releases: Vec<Release>,
+    pub(crate) releases: Vec<Release>,
+    /// The file each [`Change`] was loaded from, if any.
+    pub(crate) sources: BTreeMap<UniqueId, PathBuf>,
 }
 
 impl ChangeSet {
     /// Load from a directory (usually called `.changeset`) containing markdown files.
     ///
-    /// Any files that don't end with `.md` will be ignored.
+    /// Any files that don't end with `.md` will be ignored. The path of each file is remembered
+    /// (see [`ChangeSet::source`]) so that it can later be consumed with [`ChangeSet::consume`].
     ///
     /// # Errors
     ///
     /// 1. Directory doesn't exist
     /// 2. There's a problem loading a file (see [`Change`] for details)
     pub fn from_directory<P: AsRef<Path>>(path: P) -> Result<Self, LoadingError> {
-        path.as_ref()
-            .read_dir()?
-            .filter_map(|entry| {
-                entry
-                    .map_err(LoadingError::from)
-                    .and_then(|entry| {
-                        let path = entry.path();
-                        if path.extension().is_some_and(|ext| ext == "md") && path.is_file() {
-                            Change::from_file(path).map(Some)
-                        } else {
-                            Ok(None)
-                        }
+        let mut sources = BTreeMap::new();
+        let mut changes = Vec::new();
+        for (path, result) in change_files(path.as_ref(), &ParseOptions::default())? {
+            let change = result?;
+            sources.insert(change.unique_id.clone(), path);
+            changes.push(change);
+        }
+        Ok(Self {
+            sources,
+            ..changes.into_iter().collect()
+        })
+    }
+
+    /// Load from a directory like [`ChangeSet::from_directory`], but keep going when a file can't
+    /// be loaded.
+    ///
+    /// Every change file is attempted, the ones that load make up
+    /// [`LoadedChangeSet::changeset`] and the rest are reported in [`LoadedChangeSet::errors`].
+    /// Callers can then fail (with [`LoadedChangeSet::into_result`]), warn about the errors, or
+    /// ignore them.
+    ///
+    /// # Errors
+    ///
+    /// If the directory can't be read at all.
+    pub fn from_directory_lenient<P: AsRef<Path>>(
+        path: P,
+    ) -> Result<LoadedChangeSet, LoadingError> {
+        Self::from_directory_with_options(path, &ParseOptions::default())
+    }
+
+    /// Load from a directory like [`ChangeSet::from_directory_lenient`], parsing each file with
+    /// `options`.
+    ///
+    /// # Errors
+    ///
+    /// If the directory can't be read at all.
+    pub fn from_directory_with_options<P: AsRef<Path>>(
+        path: P,
+        options: &ParseOptions,
+    ) -> Result<LoadedChangeSet, LoadingError> {
+        let mut sources = BTreeMap::new();
+        let mut errors = Vec::new();
+        let mut changes = Vec::new();
+        for (path, result) in change_files(path.as_ref(), options)? {
+            match result {
+                Ok(change) => {
+                    sources.insert(change.unique_id.clone(), path);
+                    changes.push(change);
+                }
+                Err(error) => errors.push(FileError { path, error }),
+            }
+        }
+        Ok(LoadedChangeSet {
+            changeset: Self {
+                sources,
+                ..changes.into_iter().collect()
+            },
+            errors,
+        })
+    }
+
+    /// The [`Release`] of each package with at least one change.
+    #[must_use]
+    pub fn releases(&self) -> &[Release] {
+        &self.releases
+    }
+
+    /// Sort the [`ChangeSet::releases`].
+    ///
+    /// Without sorting, releases are in the order their packages first appear (with change files
+    /// read in order of their paths).
+    pub fn sort_releases(&mut self, order: &ReleaseOrder) {
+        match order {
+            ReleaseOrder::PackageName => self
+                .releases
+                .sort_by(|first, second| first.package_name.cmp(&second.package_name)),
+            ReleaseOrder::Custom(package_names) => self.releases.sort_by_cached_key(|release| {
+                (
+                    package_names
+                        .iter()
+                        .position(|package_name| *package_name == release.package_name)
+                        .unwrap_or(package_names.len()),
+                    release.package_name.clone(),
+                )
+            }),
+        }
+    }
+
+    /// Sort the [`Release::changes`] of every release.
+    ///
+    /// Without sorting, changes are ordered by [`ChangeOrder::UniqueId`].
+    pub fn sort_changes(&mut self, order: ChangeOrder) {
+        let created: BTreeMap<&UniqueId, Created> = match order {
+            ChangeOrder::Created => {
+                let mut created: BTreeMap<&UniqueId, Created> = self
+                    .sources
+                    .iter()
+                    .filter_map(|(unique_id, path)| {
+                        let metadata = std::fs::metadata(path).ok()?;
+                        let time = metadata.created().or_else(|_| metadata.modified()).ok()?;
+                        Some((unique_id, Created::Uncommitted(time)))
                     })
-                    .transpose()
-            })
-            .collect()
+                    .collect();
+                // Provenance is only loaded for changes with a source file
+                let committed: Vec<(&UniqueId, Created)> = self
+                    .releases
+                    .iter()
+                    .flat_map(|release| &release.changes)
+                    .filter_map(|change| {
+                        let (unique_id, _) =
+                            self.sources.get_key_value(change.unique_id.as_ref())?;
+                        let provenance = change.provenance.as_ref()?;
+                        Some((unique_id, Created::Committed(provenance.timestamp)))
+                    })
+                    .collect();
+                created.extend(committed);
+                created
+            }
+            ChangeOrder::UniqueId | ChangeOrder::ChangeType => BTreeMap::new(),
+        };
+        for release in &mut self.releases {
+            release.changes.sort_by(|first, second| {
+                let by_order = match order {
+                    ChangeOrder::UniqueId => Ordering::Equal,
+                    ChangeOrder::ChangeType => second.change_type.cmp(&first.change_type),
+                    // Changes without a known time go last
+                    ChangeOrder::Created => {
+                        match (
+                            created.get(first.unique_id.as_ref()),
+                            created.get(second.unique_id.as_ref()),
+                        ) {
+                            (Some(first), Some(second)) => first.cmp(second),
+                            (Some(_), None) => Ordering::Less,
+                            (None, Some(_)) => Ordering::Greater,
+                            (None, None) => Ordering::Equal,
+                        }
+                    }
+                };
+                by_order.then_with(|| first.unique_id.cmp(&second.unique_id))
+            });
+        }
+    }
+
+    /// The file that the [`Change`] with this ID was loaded from.
+    ///
+    /// This is only known for changes loaded with [`ChangeSet::from_directory`].
+    #[must_use]
+    pub fn source(&self, unique_id: &UniqueId) -> Option<&Path> {
+        self.sources.get(unique_id).map(PathBuf::as_path)
+    }
+}
+
+/// Attempt to load every markdown file in `dir` with `options`, along with the path each came from.
+///
+/// Files are loaded in order of their paths, so the result doesn't depend on the file system.
+fn change_files<'a>(
+    dir: &'a Path,
+    options: &'a ParseOptions,
+) -> Result<impl Iterator<Item = (PathBuf, Result<Change, LoadingError>)> + 'a, LoadingError> {
+    let mut paths = Vec::new();
+    let mut errors = Vec::new();
+    for entry in dir.read_dir()? {
+        match entry {
+            Ok(entry) => {
+                let path = entry.path();
+                if path.extension().is_some_and(|ext| ext == "md") && path.is_file() {
+                    paths.push(path);
+                }
+            }
+            Err(err) => errors.push((dir.to_path_buf(), Err(err.into()))),
+        }
+    }
+    paths.sort();
+    Ok(errors.into_iter().chain(paths.into_iter().map(|path| {
+        let change = options.load_file(&path);
+        (path, change)
+    })))
+}
+
+/// How to sort releases with [`ChangeSet::sort_releases`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ReleaseOrder {
+    /// Alphabetically by [`Release::package_name`].
+    #[default]
+    PackageName,
+    /// In the order of this list (for example, the topological order of a workspace). Packages
+    /// which aren't in the list go last, sorted by name.
+    Custom(Vec<PackageName>),
+}
+
+/// How to sort the changes in each [`Release`] with [`ChangeSet::sort_changes`].
+///
+/// Ties are always broken by [`PackageChange::unique_id`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ChangeOrder {
+    /// By [`PackageChange::unique_id`] (the name of the change file).
+    #[default]
+    UniqueId,
+    /// Largest [`ChangeType`] first.
+    ChangeType,
+    /// Oldest first, according to the commit which added each change file, if
+    /// [`ChangeSet::load_provenance`] was used. This is the same in every checkout of a
+    /// repository.
+    ///
+    /// Changes without a [`PackageChange::provenance`] (like uncommitted change files) come after
+    /// that, ordered by when their file was created (or last modified, if the file system
+    /// doesn't record creation times). File times depend on the checkout, so this part of the
+    /// order isn't reproducible. Changes without a known source file go last.
+    Created,
+}
+
+/// When a change was created, see [`ChangeOrder::Created`]. Committed changes come first.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+enum Created {
+    /// The Unix timestamp of the commit which added the change file.
+    Committed(i64),
+    /// When the change file was created in this checkout.
+    Uncommitted(SystemTime),
+}
+
+/// The result of [`ChangeSet::from_directory_lenient`]: every change that could be loaded, and
+/// the problem with each file that couldn't.
+#[derive(Debug)]
+pub struct LoadedChangeSet {
+    pub changeset: ChangeSet,
+    pub errors: Vec<FileError>,
+}
+
+impl LoadedChangeSet {
+    /// Fail if any file couldn't be loaded, otherwise return the [`ChangeSet`].
+    ///
+    /// # Errors
+    ///
+    /// Every [`FileError`] if there were any.
+    pub fn into_result(self) -> Result<ChangeSet, Vec<FileError>> {
+        if self.errors.is_empty() {
+            Ok(self.changeset)
+        } else {
+            Err(self.errors)
+        }
+    }
+}
+
+/// A change file which couldn't be loaded, see [`ChangeSet::from_directory_lenient`].
+#[derive(Debug)]
+pub struct FileError {
+    pub path: PathBuf,
+    pub error: LoadingError,
+}
+
+impl Display for FileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.error {
+            // Parsing errors already include the path
+            LoadingError::Parsing(err) if err.path.is_some() => Display::fmt(err, f),
+            err => write!(f, "{}: {err}", self.path.display()),
+        }
+    }
+}
+
+impl Error for FileError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
     }
 }
 
@@ -57,6 +320,7 @@ impl FromIterator<Change> for ChangeSet {
                                 change_type,
                                 unique_id: unique_id.clone(),
                                 summary: summary.clone(),
+                                provenance: None,
                             },
                         )
                     })
@@ -73,6 +337,7 @@ impl FromIterator<Change> for ChangeSet {
                         releases.push(Release {
                             package_name,
                             changes: vec![change],
+                            minimum_change_type: None,
                         });
                     }
                     releases
@@ -83,7 +348,10 @@ impl FromIterator<Change> for ChangeSet {
                 .changes
                 .sort_by(|first, second| first.unique_id.cmp(&second.unique_id));
         }
-        Self { releases }
+        Self {
+            releases,
+            sources: BTreeMap::new(),
+        }
     }
 }
 
@@ -104,21 +372,56 @@ impl From<ChangeSet> for Vec<Release> {
 
 /// The combination of applicable [`Change`]s in a [`ChangeSet`] for a single package.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Release {
     pub package_name: PackageName,
     pub changes: Vec<PackageChange>,
+    /// Release with at least this [`ChangeType`], even if none of the [`Release::changes`] require
+    /// it. This is set by [`crate::Planner`] for packages in a [`crate::Group`].
+    pub minimum_change_type: Option<ChangeType>,
 }
 
 impl Release {
-    /// The overall [`ChangeType`] for the package's version based on all the [`Release::changes`].
+    /// The overall [`ChangeType`] for the package's version based on all the [`Release::changes`]
+    /// and [`Release::minimum_change_type`].
+    ///
+    /// [`ChangeType::None`] is ignored, so this is `None` if there are only those changes.
     #[must_use]
     pub fn change_type(&self) -> Option<&ChangeType> {
-        self.changes.iter().map(|change| &change.change_type).max()
+        self.changes
+            .iter()
+            .map(|change| &change.change_type)
+            .chain(self.minimum_change_type.as_ref())
+            .filter(|change_type| **change_type != ChangeType::None)
+            .max()
+    }
+
+    /// The largest [`Bump`] required by any of the [`Release::changes`] or the
+    /// [`Release::minimum_change_type`], according to `rules`.
+    #[must_use]
+    pub fn bump(&self, rules: &BumpRules) -> Option<Bump> {
+        self.changes
+            .iter()
+            .map(|change| &change.change_type)
+            .chain(self.minimum_change_type.as_ref())
+            .filter_map(|change_type| rules.bump_for(change_type))
+            .max()
+    }
+
+    /// The version this package should be released as, given its `current` version.
+    ///
+    /// The largest [`Release::bump`] is applied. If there is nothing to bump, `current` is
+    /// returned unchanged. Returns `None` if the bump would overflow (see [`Version::bump`]).
+    #[must_use]
+    pub fn next_version(&self, current: &Version, rules: &BumpRules) -> Option<Version> {
+        self.bump(rules)
+            .map_or_else(|| Some(current.clone()), |bump| current.bump(bump, rules))
     }
 }
 
 /// A [`Change`] as it applies to a single package for a [`Release`],
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PackageChange {
     /// The ID of the originating [`Change`].
     pub unique_id: Arc<UniqueId>,
@@ -126,4 +429,10 @@ pub struct PackageChange {
     pub change_type: ChangeType,
     /// The details of the change, as a markdown-formatted string.
     pub summary: Arc<str>,
+    /// The commit which added the change file, if loaded with [`ChangeSet::load_provenance`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub provenance: Option<Arc<Provenance>>,
 }
@@ -0,0 +1,371 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    error::Error,
+    fmt::{Display, Formatter},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::{
+    Change, ChangeFile, ChangeSet, ChangeType, PackageName, ParseOptions, Release, UniqueId,
+    Versioning,
+};
+
+impl ChangeSet {
+    /// Plan how to consume the change files which contributed to `releases`, usually after those
+    /// releases have been made.
+    ///
+    /// If every package in a change file was released, the file is consumed according to `mode`.
+    /// If some packages in the file were _not_ released (for example, when using
+    /// [`crate::Planner::packages`]), the file is instead rewritten to only contain the packages
+    /// that still need to be released.
+    ///
+    /// Nothing is changed on disk until [`ConsumePlan::execute`] is called, so the plan can be
+    /// displayed as a dry run first. Only changes whose source file is known (see
+    /// [`ChangeSet::source`]) are included.
+    #[must_use]
+    pub fn consume(&self, releases: &[Release], mode: &ConsumeMode) -> ConsumePlan {
+        let mut consumed: BTreeMap<&UniqueId, (BTreeSet<&PackageName>, &Arc<str>)> =
+            BTreeMap::new();
+        for release in releases {
+            for change in &release.changes {
+                consumed
+                    .entry(change.unique_id.as_ref())
+                    .or_insert_with(|| (BTreeSet::new(), &change.summary))
+                    .0
+                    .insert(&release.package_name);
+            }
+        }
+        let actions = consumed
+            .into_iter()
+            .filter_map(|(unique_id, (packages, summary))| {
+                let path = self.source(unique_id)?;
+                let remaining: Vec<(PackageName, ChangeType)> = self
+                    .releases()
+                    .iter()
+                    .filter(|release| !packages.contains(&release.package_name))
+                    .filter_map(|release| {
+                        let change = release
+                            .changes
+                            .iter()
+                            .find(|change| change.unique_id.as_ref() == unique_id)?;
+                        Some((release.package_name.clone(), change.change_type.clone()))
+                    })
+                    .collect();
+                let action = if let Ok(versioning) = Versioning::try_from_iter(remaining) {
+                    ConsumeAction::Rewrite {
+                        path: path.to_path_buf(),
+                        change: Change {
+                            unique_id: unique_id.clone(),
+                            versioning,
+                            summary: summary.to_string(),
+                        },
+                    }
+                } else {
+                    match mode {
+                        ConsumeMode::Delete => ConsumeAction::Delete(path.to_path_buf()),
+                        ConsumeMode::Archive(directory) => ConsumeAction::Move {
+                            from: path.to_path_buf(),
+                            to: directory.join(path.file_name().unwrap_or(path.as_os_str())),
+                        },
+                    }
+                };
+                Some(action)
+            })
+            .collect();
+        ConsumePlan { actions }
+    }
+}
+
+/// What should happen to change files once they've been released.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ConsumeMode {
+    /// Delete the files.
+    Delete,
+    /// Move the files into this directory, which is created if it doesn't exist. Files already in
+    /// the directory with the same name are never overwritten, the plan fails instead.
+    Archive(PathBuf),
+}
+
+/// A single file operation in a [`ConsumePlan`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ConsumeAction {
+    Delete(PathBuf),
+    /// Move a change file, failing (rather than overwriting it) if `to` already exists.
+    Move {
+        from: PathBuf,
+        to: PathBuf,
+    },
+    /// Replace the contents of a change file which still has unreleased packages.
+    ///
+    /// Only the lines of released packages are removed, the rest of the file keeps its original
+    /// formatting (see [`ChangeFile`]).
+    Rewrite {
+        path: PathBuf,
+        change: Change,
+    },
+}
+
+impl ConsumeAction {
+    fn apply(&self) -> std::io::Result<Undo> {
+        match self {
+            Self::Delete(path) => {
+                let contents = std::fs::read(path)?;
+                std::fs::remove_file(path)?;
+                Ok(Undo::Write {
+                    path: path.clone(),
+                    contents,
+                })
+            }
+            Self::Move { from, to } => {
+                let created = match to.parent() {
+                    Some(parent) => create_dir_all(parent)?,
+                    None => None,
+                };
+                if let Err(err) = move_new(from, to) {
+                    if let (Some(created), Some(parent)) = (&created, to.parent()) {
+                        // The original error is more useful than one from cleaning up
+                        remove_created_dirs(parent, created).ok();
+                    }
+                    return Err(err);
+                }
+                Ok(Undo::Move {
+                    from: to.clone(),
+                    to: from.clone(),
+                    created,
+                })
+            }
+            Self::Rewrite { path, change } => {
+                let contents = std::fs::read(path)?;
+                std::fs::write(path, rewritten(&contents, change))?;
+                Ok(Undo::Write {
+                    path: path.clone(),
+                    contents,
+                })
+            }
+        }
+    }
+}
+
+/// Like [`std::fs::create_dir_all`], but returns the outermost directory that was created (if
+/// any) so it can be removed again.
+fn create_dir_all(path: &Path) -> std::io::Result<Option<PathBuf>> {
+    let mut created = None;
+    for ancestor in path.ancestors() {
+        if ancestor.as_os_str().is_empty() || ancestor.try_exists()? {
+            break;
+        }
+        created = Some(ancestor.to_path_buf());
+    }
+    std::fs::create_dir_all(path)?;
+    Ok(created)
+}
+
+/// Remove `path` and each of its parents up to and including `created`, which must be empty.
+fn remove_created_dirs(path: &Path, created: &Path) -> std::io::Result<()> {
+    for ancestor in path.ancestors() {
+        std::fs::remove_dir(ancestor)?;
+        if ancestor == created {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Move `from` to `to`, failing if `to` already exists.
+///
+/// Checking for `to` and then renaming would be a race, so the file is copied into a newly
+/// created `to` instead.
+fn move_new(from: &Path, to: &Path) -> std::io::Result<()> {
+    let mut source = std::fs::File::open(from)?;
+    let mut destination = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(to)
+        .map_err(|err| {
+            if err.kind() == std::io::ErrorKind::AlreadyExists {
+                std::io::Error::new(err.kind(), format!("{} already exists", to.display()))
+            } else {
+                err
+            }
+        })?;
+    let copied = std::io::copy(&mut source, &mut destination)
+        .and_then(|_| destination.sync_all())
+        .and_then(|()| std::fs::remove_file(from));
+    if let Err(err) = copied {
+        drop(destination);
+        // `from` is still intact, so `to` is only a partial copy
+        std::fs::remove_file(to)?;
+        return Err(err);
+    }
+    Ok(())
+}
+
+/// The new contents of a change file which should only contain `change`, keeping the original
+/// formatting of `contents` if possible.
+fn rewritten(contents: &[u8], change: &Change) -> String {
+    let Some(mut file) = std::str::from_utf8(contents)
+        .ok()
+        .and_then(|contents| ChangeFile::parse(contents, &ParseOptions::default()).ok())
+    else {
+        return change.to_string();
+    };
+    let removed: Vec<PackageName> = file
+        .packages()
+        .into_iter()
+        .map(|(package_name, _)| package_name)
+        .filter(|package_name| change.versioning.get(package_name).is_none())
+        .cloned()
+        .collect();
+    for package_name in removed {
+        file.remove_package(&package_name);
+    }
+    for (package_name, change_type) in change.versioning.iter() {
+        if file.change_type(package_name) != Some(change_type) {
+            file.set_change_type(package_name, change_type.clone());
+        }
+    }
+    // Compare the way the summary was loaded, so line endings alone don't count as an edit
+    if file.change_summary() != change.summary {
+        file.set_summary(&change.summary);
+    }
+    file.to_string()
+}
+
+impl Display for ConsumeAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Delete(path) => write!(f, "delete {}", path.display()),
+            Self::Move { from, to } => write!(f, "move {} to {}", from.display(), to.display()),
+            Self::Rewrite { path, change } => {
+                write!(f, "rewrite {} to only include ", path.display())?;
+                for (index, (package_name, _)) in change.versioning.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(", ")?;
+                    }
+                    f.write_str(package_name)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// How to reverse a [`ConsumeAction`] which was already applied.
+enum Undo {
+    Write {
+        path: PathBuf,
+        contents: Vec<u8>,
+    },
+    Move {
+        from: PathBuf,
+        to: PathBuf,
+        /// The outermost directory which was created for `from`, to be removed afterwards.
+        created: Option<PathBuf>,
+    },
+}
+
+impl Undo {
+    fn apply(self) -> std::io::Result<()> {
+        match self {
+            Self::Write { path, contents } => std::fs::write(path, contents),
+            Self::Move { from, to, created } => {
+                std::fs::rename(&from, to)?;
+                match (created, from.parent()) {
+                    (Some(created), Some(parent)) => remove_created_dirs(parent, &created),
+                    _ => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+/// The file operations needed to consume a [`ChangeSet`], created by [`ChangeSet::consume`].
+///
+/// Display this to report what would happen (a dry run), or call [`ConsumePlan::execute`] to
+/// actually do it.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConsumePlan {
+    pub actions: Vec<ConsumeAction>,
+}
+
+impl ConsumePlan {
+    /// Apply every [`ConsumeAction`] in order.
+    ///
+    /// # Errors
+    ///
+    /// If any action fails, every action which was already applied is reversed (restoring deleted
+    /// files, moving archived files back, and removing any archive directory that was created)
+    /// before returning a [`ConsumeError`].
+    pub fn execute(&self) -> Result<(), ConsumeError> {
+        let mut applied = Vec::with_capacity(self.actions.len());
+        for action in &self.actions {
+            match action.apply() {
+                Ok(undo) => applied.push(undo),
+                Err(source) => {
+                    let rollback_errors = applied
+                        .into_iter()
+                        .rev()
+                        .filter_map(|undo| undo.apply().err())
+                        .collect();
+                    return Err(ConsumeError {
+                        action: Box::new(action.clone()),
+                        source,
+                        rollback_errors,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Every file which will be removed from its current location or rewritten.
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.actions.iter().map(|action| match action {
+            ConsumeAction::Delete(path)
+            | ConsumeAction::Move { from: path, .. }
+            | ConsumeAction::Rewrite { path, .. } => path.as_path(),
+        })
+    }
+}
+
+impl Display for ConsumePlan {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for action in &self.actions {
+            writeln!(f, "{action}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The error that occurs when a [`ConsumePlan`] can't be executed.
+#[derive(Debug)]
+pub struct ConsumeError {
+    /// The action which failed.
+    pub action: Box<ConsumeAction>,
+    pub source: std::io::Error,
+    /// Any errors that occurred while reversing the actions which were already applied. If this
+    /// is empty, the files are back in their original state.
+    pub rollback_errors: Vec<std::io::Error>,
+}
+
+impl Display for ConsumeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "could not {}: {}", self.action, self.source)?;
+        for err in &self.rollback_errors {
+            write!(f, "; rollback also failed: {err}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ConsumeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
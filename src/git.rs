@@ -0,0 +1,328 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    error::Error,
+    fmt::{Display, Formatter},
+    path::{Component, Path, PathBuf},
+    process::Command,
+    sync::Arc,
+};
+
+use crate::{ChangeSet, FileError, PackageName, ParseOptions};
+
+/// A local git repository, accessed by running the `git` command.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GitRepository {
+    root: PathBuf,
+}
+
+impl GitRepository {
+    /// Open the repository containing `path`.
+    ///
+    /// # Errors
+    ///
+    /// - If `git` can't be run, or `path` isn't in a git repository
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, GitError> {
+        let output = run_git(path.as_ref(), &["rev-parse", "--show-toplevel"])?;
+        Ok(Self {
+            root: PathBuf::from(output.trim_end_matches('\n')),
+        })
+    }
+
+    /// The top-level directory of the working tree.
+    #[must_use]
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// The commit where `HEAD` branched off from `base` (any revision, like `origin/main`).
+    ///
+    /// # Errors
+    ///
+    /// - If `git` fails, for example because `base` doesn't exist
+    pub fn merge_base(&self, base: &str) -> Result<String, GitError> {
+        let output = self.git(&["merge-base", base, "HEAD"])?;
+        Ok(output.trim().to_string())
+    }
+
+    /// Every file which is different in the working tree than in `commit`, relative to
+    /// [`GitRepository::root`]. This includes committed, uncommitted, and untracked (but not
+    /// ignored) files.
+    ///
+    /// # Errors
+    ///
+    /// - If `git` fails
+    pub fn changed_files(&self, commit: &str) -> Result<Vec<ChangedFile>, GitError> {
+        let diff = self.git(&["diff", "--name-status", "--no-renames", "-z", commit, "--"])?;
+        let mut fields = diff.split('\0').filter(|field| !field.is_empty());
+        let mut files = Vec::new();
+        while let (Some(status), Some(path)) = (fields.next(), fields.next()) {
+            files.push(ChangedFile {
+                path: PathBuf::from(path),
+                added: status == "A",
+            });
+        }
+        let untracked = self.git(&["ls-files", "--others", "--exclude-standard", "-z"])?;
+        files.extend(
+            untracked
+                .split('\0')
+                .filter(|path| !path.is_empty())
+                .map(|path| ChangedFile {
+                    path: PathBuf::from(path),
+                    added: true,
+                }),
+        );
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(files)
+    }
+
+    /// Find the packages which changed since `HEAD` branched off from `base`, but aren't in any
+    /// change file added since then.
+    ///
+    /// `packages` maps each package to its directory, either absolute or relative to
+    /// [`GitRepository::root`] (see [`crate::Workspace::package_paths`]). Each changed file
+    /// belongs to the package with the most specific directory containing it, if any. Files in
+    /// `changeset_directory` (like `.changeset`) are never part of a
+    /// package. A change file covers every package it names, even with [`crate::ChangeType::None`].
+    ///
+    /// # Errors
+    ///
+    /// - If `git` fails, for example because `base` doesn't exist
+    /// - If a change file which was added can't be loaded
+    pub fn uncovered_packages<P: AsRef<Path>>(
+        &self,
+        base: &str,
+        packages: &BTreeMap<PackageName, PathBuf>,
+        changeset_directory: P,
+    ) -> Result<Vec<UncoveredPackage>, GitError> {
+        let changeset_directory = self.relative(changeset_directory.as_ref());
+        let merge_base = self.merge_base(base)?;
+        let packages: Vec<(&PackageName, PathBuf)> = packages
+            .iter()
+            .map(|(name, path)| (name, self.relative(path)))
+            .collect();
+
+        let mut covered = BTreeSet::new();
+        let mut changed: BTreeMap<&PackageName, Vec<PathBuf>> = BTreeMap::new();
+        for file in self.changed_files(&merge_base)? {
+            if file.path.parent() == Some(changeset_directory.as_path()) {
+                if file.added && file.path.extension().is_some_and(|ext| ext == "md") {
+                    let path = self.root.join(&file.path);
+                    let change = ParseOptions::default()
+                        .load_file(&path)
+                        .map_err(|error| GitError::InvalidChangeFile(FileError { path, error }))?;
+                    covered.extend(change.versioning.into_iter().map(|(name, _)| name));
+                }
+                continue;
+            }
+            let package = packages
+                .iter()
+                .filter(|(_, directory)| file.path.starts_with(directory))
+                .max_by_key(|(_, directory)| directory.components().count());
+            if let Some((name, _)) = package {
+                changed.entry(name).or_default().push(file.path);
+            }
+        }
+        Ok(changed
+            .into_iter()
+            .filter(|(name, _)| !covered.contains(*name))
+            .map(|(name, files)| UncoveredPackage {
+                package_name: name.clone(),
+                files,
+            })
+            .collect())
+    }
+
+    /// The commit which first added the file at `path` (absolute or relative to
+    /// [`GitRepository::root`]), or `None` if it hasn't been committed.
+    ///
+    /// # Errors
+    ///
+    /// - If `git` fails, for example because `path` is outside of the repository
+    pub fn provenance<P: AsRef<Path>>(&self, path: P) -> Result<Option<Provenance>, GitError> {
+        let path = self.relative(path.as_ref());
+        let log = self.git(&[
+            "log",
+            "--diff-filter=A",
+            "--format=%H%x00%an%x00%ae%x00%aI%x00%at%x00%B%x00",
+            "--",
+            &path.to_string_lossy(),
+        ])?;
+        let fields: Vec<&str> = log.split('\0').collect();
+        // Commits are listed newest first, and a file may have been deleted and added again
+        let Some([commit, author_name, author_email, date, timestamp, message]) =
+            fields.chunks_exact(6).last()
+        else {
+            return Ok(None);
+        };
+        Ok(Some(Provenance {
+            commit: commit.trim().to_string(),
+            author_name: (*author_name).to_string(),
+            author_email: (*author_email).to_string(),
+            date: (*date).to_string(),
+            timestamp: timestamp.parse().map_err(|_| {
+                GitError::UnexpectedOutput(format!("invalid timestamp `{timestamp}`"))
+            })?,
+            message: message.trim_end().to_string(),
+        }))
+    }
+
+    /// `path` relative to the root, resolving symlinks (like `/tmp` on macOS) if it's absolute.
+    ///
+    /// `.` components are removed, so `./crates/core` is `crates/core` (and `.` is empty) like
+    /// the paths git reports.
+    fn relative(&self, path: &Path) -> PathBuf {
+        let path = if path.is_relative() {
+            path.to_path_buf()
+        } else {
+            let root = self
+                .root
+                .canonicalize()
+                .unwrap_or_else(|_| self.root.clone());
+            let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+            path.strip_prefix(&root).unwrap_or(&path).to_path_buf()
+        };
+        path.components()
+            .filter(|component| *component != Component::CurDir)
+            .collect()
+    }
+
+    fn git(&self, args: &[&str]) -> Result<String, GitError> {
+        run_git(&self.root, args)
+    }
+}
+
+fn run_git(directory: &Path, args: &[&str]) -> Result<String, GitError> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(directory)
+        .args(args)
+        .output()
+        .map_err(GitError::Io)?;
+    if !output.status.success() {
+        return Err(GitError::Command {
+            args: args.join(" "),
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+impl ChangeSet {
+    /// Set the [`crate::PackageChange::provenance`] of every change which was loaded from a file
+    /// (see [`ChangeSet::source`]) to the commit which first added that file.
+    ///
+    /// Changes whose files haven't been committed yet are left without provenance.
+    ///
+    /// # Errors
+    ///
+    /// - If `git` fails, for example because the change files are outside of `repository`
+    /// - If the current directory can't be read, which is needed for relative paths
+    pub fn load_provenance(&mut self, repository: &GitRepository) -> Result<(), GitError> {
+        let mut provenances = BTreeMap::new();
+        for (unique_id, path) in &self.sources {
+            // Sources are relative to the current directory, not the root of the repository
+            let path = std::path::absolute(path).map_err(GitError::Io)?;
+            if let Some(provenance) = repository.provenance(path)? {
+                provenances.insert(unique_id, Arc::new(provenance));
+            }
+        }
+        for release in &mut self.releases {
+            for change in &mut release.changes {
+                change.provenance = provenances.get(change.unique_id.as_ref()).cloned();
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Where a [`crate::Change`] came from, see [`GitRepository::provenance`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Provenance {
+    /// The full hash of the commit.
+    pub commit: String,
+    pub author_name: String,
+    pub author_email: String,
+    /// When the commit was authored, in strict ISO 8601 format (like
+    /// `2025-03-09T12:34:56+01:00`).
+    pub date: String,
+    /// [`Provenance::date`] as seconds since the Unix epoch, for comparing dates in different
+    /// time zones.
+    pub timestamp: i64,
+    /// The full commit message.
+    pub message: String,
+}
+
+/// A file which changed in a [`GitRepository`], see [`GitRepository::changed_files`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChangedFile {
+    /// The path relative to [`GitRepository::root`].
+    pub path: PathBuf,
+    /// Whether the file didn't exist before.
+    pub added: bool,
+}
+
+/// A package which changed without a change file, see [`GitRepository::uncovered_packages`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UncoveredPackage {
+    pub package_name: PackageName,
+    /// The changed files in the package, relative to [`GitRepository::root`].
+    pub files: Vec<PathBuf>,
+}
+
+impl Display for UncoveredPackage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "`{}` has changes without a change file (",
+            self.package_name
+        )?;
+        for (index, file) in self.files.iter().enumerate() {
+            if index > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}", file.display())?;
+        }
+        f.write_str(")")
+    }
+}
+
+#[derive(Debug)]
+pub enum GitError {
+    /// `git` couldn't be run at all, or the current directory couldn't be read.
+    Io(std::io::Error),
+    /// `git` failed.
+    Command {
+        /// The arguments `git` was run with.
+        args: String,
+        stderr: String,
+    },
+    InvalidChangeFile(FileError),
+    /// `git` succeeded, but its output couldn't be understood.
+    UnexpectedOutput(String),
+}
+
+impl Display for GitError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GitError::Io(err) => write!(f, "could not run git: {err}"),
+            GitError::Command { args, stderr } => write!(f, "`git {args}` failed: {stderr}"),
+            GitError::InvalidChangeFile(err) => Display::fmt(err, f),
+            GitError::UnexpectedOutput(message) => {
+                write!(f, "unexpected output from git: {message}")
+            }
+        }
+    }
+}
+
+impl Error for GitError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GitError::Io(err) => Some(err),
+            GitError::Command { .. } | GitError::UnexpectedOutput(_) => None,
+            GitError::InvalidChangeFile(err) => Some(err),
+        }
+    }
+}
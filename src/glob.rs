@@ -0,0 +1,173 @@
+use std::path::{Path, PathBuf};
+
+/// Every directory under `root` which matches `pattern` (like `crates/*` or `packages/**`),
+/// sorted.
+///
+/// Each `/`-separated component of the pattern may contain `*` (any characters) and `?` (any
+/// single character). A `**` component matches any number of directories, but doesn't descend into
+/// symlinks (which could form a cycle). Wildcards don't match hidden directories or
+/// `node_modules`.
+pub(crate) fn expand_directories(root: &Path, pattern: &str) -> Vec<PathBuf> {
+    let components: Vec<&str> = pattern
+        .split('/')
+        .filter(|component| !component.is_empty() && *component != ".")
+        .collect();
+    let mut directories = Vec::new();
+    expand(root.to_path_buf(), &components, &mut directories);
+    directories.sort();
+    directories.dedup();
+    directories
+}
+
+fn expand(dir: PathBuf, components: &[&str], directories: &mut Vec<PathBuf>) {
+    let Some((component, rest)) = components.split_first() else {
+        directories.push(dir);
+        return;
+    };
+    if *component == "**" {
+        for subdirectory in subdirectories(&dir, false) {
+            expand(subdirectory, components, directories);
+        }
+        expand(dir, rest, directories);
+    } else if component.contains(['*', '?']) {
+        for subdirectory in subdirectories(&dir, true) {
+            if subdirectory
+                .file_name()
+                .is_some_and(|name| matches(component, &name.to_string_lossy()))
+            {
+                expand(subdirectory, rest, directories);
+            }
+        }
+    } else {
+        let next = dir.join(component);
+        if next.is_dir() {
+            expand(next, rest, directories);
+        }
+    }
+}
+
+/// The directories in `dir` which wildcards may match, including symlinks to directories only if
+/// `follow_symlinks` is set.
+fn subdirectories(dir: &Path, follow_symlinks: bool) -> Vec<PathBuf> {
+    let Ok(entries) = dir.read_dir() else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            !name.starts_with('.') && name != "node_modules"
+        })
+        .filter(|entry| {
+            entry.file_type().is_ok_and(|file_type| {
+                file_type.is_dir()
+                    || (follow_symlinks && file_type.is_symlink() && entry.path().is_dir())
+            })
+        })
+        .map(|entry| entry.path())
+        .collect()
+}
+
+/// Whether `name` matches a single pattern component containing `*` and `?`.
+fn matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    // The position in each after the last `*`, to backtrack to when a match fails
+    let mut backtrack = None;
+    let (mut p, mut n) = (0, 0);
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p + 1, n));
+                p += 1;
+            }
+            Some(c) if *c == '?' || Some(c) == name.get(n) => {
+                p += 1;
+                n += 1;
+            }
+            _ => {
+                let Some((star_p, star_n)) = backtrack else {
+                    return false;
+                };
+                backtrack = Some((star_p, star_n + 1));
+                p = star_p;
+                n = star_n + 1;
+            }
+        }
+    }
+    pattern.iter().skip(p).all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod test_glob {
+    use super::*;
+
+    #[test]
+    fn wildcards() {
+        assert!(matches("*", "anything"));
+        assert!(matches("lib-*", "lib-core"));
+        assert!(!matches("lib-*", "core"));
+        assert!(matches("*-cli", "my-cli"));
+        assert!(matches("a*b*c", "aXXbYYc"));
+        assert!(!matches("a*b*c", "aXXbYY"));
+        assert!(matches("v?", "v1"));
+        assert!(!matches("v?", "v10"));
+    }
+
+    #[test]
+    fn directories() {
+        let dir = tempfile::tempdir().unwrap();
+        for path in [
+            "crates/a",
+            "crates/b/nested",
+            "crates/.hidden",
+            "packages/node_modules/dep",
+        ] {
+            std::fs::create_dir_all(dir.path().join(path)).unwrap();
+        }
+        assert_eq!(
+            expand_directories(dir.path(), "./crates/*"),
+            vec![dir.path().join("crates/a"), dir.path().join("crates/b")]
+        );
+        assert_eq!(
+            expand_directories(dir.path(), "crates/**"),
+            vec![
+                dir.path().join("crates"),
+                dir.path().join("crates/a"),
+                dir.path().join("crates/b"),
+                dir.path().join("crates/b/nested"),
+            ]
+        );
+        assert_eq!(
+            expand_directories(dir.path(), "crates/a"),
+            vec![dir.path().join("crates/a")]
+        );
+        assert!(expand_directories(dir.path(), "packages/*").is_empty());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("packages/a")).unwrap();
+        std::fs::create_dir_all(dir.path().join("shared")).unwrap();
+        // A cycle, like the links package managers create
+        std::os::unix::fs::symlink(
+            dir.path().join("packages"),
+            dir.path().join("packages/a/loop"),
+        )
+        .unwrap();
+        std::os::unix::fs::symlink(dir.path().join("shared"), dir.path().join("packages/b"))
+            .unwrap();
+
+        assert_eq!(
+            expand_directories(dir.path(), "packages/**"),
+            vec![dir.path().join("packages"), dir.path().join("packages/a")]
+        );
+        assert_eq!(
+            expand_directories(dir.path(), "packages/*"),
+            vec![dir.path().join("packages/a"), dir.path().join("packages/b")]
+        );
+    }
+}
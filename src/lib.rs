@@ -18,10 +18,65 @@
     )
 )]
 
-pub use change::{Change, LoadingError, ParsingError, UniqueId};
-pub use changeset::{ChangeSet, PackageChange, Release};
+/// Implement `serde::Serialize` and `serde::Deserialize` for a type using its `Display` and
+/// `FromStr` implementations, so that it's represented as a plain string.
+#[cfg(feature = "serde")]
+macro_rules! serde_as_string {
+    ($type:ty) => {
+        impl serde::Serialize for $type {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $type {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let value = <String as serde::Deserialize>::deserialize(deserializer)?;
+                value.parse().map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+pub use change::{
+    Change, DuplicatePackages, LoadingError, ParseOptions, ParsingError, ParsingErrorKind, UniqueId,
+};
+pub use change_file::ChangeFile;
+pub use changelog::{
+    Changelog, ChangelogEntry, ChangelogFormat, ChangelogSection, InsertError, ReleaseNotes,
+    ReleaseNotesSection,
+};
+pub use changeset::{
+    ChangeOrder, ChangeSet, FileError, LoadedChangeSet, PackageChange, Release, ReleaseOrder,
+};
+pub use consume::{ConsumeAction, ConsumeError, ConsumeMode, ConsumePlan};
+pub use git::{ChangedFile, GitError, GitRepository, Provenance, UncoveredPackage};
+pub use manifest::{ManifestError, ManifestPlan, ManifestUpdate};
+#[cfg(feature = "json")]
+pub use npm_config::{Access, NpmConfig, NpmConfigError, UpdateInternalDependencies};
+pub use plan::{Dependency, Group, Planner, Propagation};
+pub use pre_mode::PreMode;
+pub use release_plan::{PlannedChange, PlannedRelease, ReleasePlan};
+pub use requirement::VersionReq;
+pub use version::{Bump, BumpRules, ParseVersionError, Prerelease, PrereleaseIdentifier, Version};
 pub use versioning::{BuildVersioningError, ChangeType, PackageName, Versioning};
+pub use workspace::{UnknownPackage, Workspace, WorkspaceError, WorkspacePackage};
 
 mod change;
+mod change_file;
+mod changelog;
 mod changeset;
+mod consume;
+mod git;
+#[cfg(any(feature = "json", feature = "toml"))]
+mod glob;
+mod manifest;
+#[cfg(feature = "json")]
+mod npm_config;
+mod plan;
+mod pre_mode;
+mod release_plan;
+mod requirement;
+mod version;
 mod versioning;
+mod workspace;
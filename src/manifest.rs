@@ -0,0 +1,830 @@
+#[cfg(feature = "json")]
+use std::ops::Range;
+#[cfg(feature = "toml")]
+use std::str::FromStr;
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fmt::{Display, Formatter},
+    path::{Path, PathBuf},
+};
+
+#[cfg(feature = "toml")]
+use toml_edit::{DocumentMut, Item, TableLike};
+
+use crate::{PackageName, ReleasePlan, Version, Workspace, WorkspaceError};
+
+impl ReleasePlan {
+    /// Plan how to write the new versions of this plan's releases into `manifests`.
+    ///
+    /// The supported manifests are `Cargo.toml`, `Cargo.lock`, `package.json`, and
+    /// `pyproject.toml` (using `[project]` or `[tool.poetry]`), chosen by file name. Updating
+    /// `package.json` requires the `json` feature, and the others require the `toml` feature.
+    /// The version of each released package is updated, along with the requirements of
+    /// dependencies on released packages (see below). Everything else in the files, including
+    /// formatting and comments, stays the same.
+    ///
+    /// A dependency requirement is only updated if it's a single version, keeping its operator
+    /// (so `^1.2.3` becomes `^2.0.0`). Other requirements (like `*`, `workspace:^`, or
+    /// `>=1, <2`) are left alone. In `Cargo.toml`, only dependencies with a `path` are updated.
+    /// Dependencies in `pyproject.toml` aren't updated.
+    ///
+    /// Cargo packages which inherit `version.workspace = true` are updated by changing the
+    /// `[workspace.package]` version of their workspace root (the closest `Cargo.toml` with a
+    /// `[workspace]` table), which is included in the plan even if it's not in `manifests`. This
+    /// only works if every package in `manifests` inheriting that version gets the same new
+    /// version.
+    ///
+    /// Nothing is changed on disk until [`ManifestPlan::execute`] is called, so the plan can be
+    /// displayed as a diff first (a dry run). Manifests which don't need any changes aren't
+    /// included.
+    ///
+    /// # Errors
+    ///
+    /// - If a manifest can't be read, isn't valid, or isn't one of the supported kinds
+    /// - If packages which inherit the same workspace version would get different versions (or
+    ///   only some of them are released)
+    pub fn update_manifests<I, P>(&self, manifests: I) -> Result<ManifestPlan, WorkspaceError>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let versions: BTreeMap<PackageName, Version> = self
+            .releases
+            .iter()
+            .filter_map(|release| {
+                Some((release.package_name.clone(), release.new_version.clone()?))
+            })
+            .collect();
+        #[cfg_attr(not(feature = "toml"), allow(unused_mut))]
+        let mut manifests: Vec<(PathBuf, String)> = manifests
+            .into_iter()
+            .map(|path| {
+                let path = path.as_ref();
+                read_manifest(path).map(|contents| (path.to_path_buf(), contents))
+            })
+            .collect::<Result<_, _>>()?;
+        #[cfg(feature = "toml")]
+        let workspace_versions = workspace_versions(&mut manifests, &versions)?;
+        #[cfg(not(feature = "toml"))]
+        let workspace_versions: BTreeMap<PathBuf, Version> = BTreeMap::new();
+
+        let mut plan = ManifestPlan::default();
+        for (path, original) in manifests {
+            let updated =
+                updated_manifest(&path, &original, &versions, workspace_versions.get(&path))
+                    .map_err(|message| WorkspaceError::InvalidManifest {
+                        path: path.clone(),
+                        message,
+                    })?;
+            if updated != original {
+                plan.updates.push(ManifestUpdate {
+                    path,
+                    original,
+                    updated,
+                });
+            }
+        }
+        Ok(plan)
+    }
+}
+
+fn read_manifest(path: &Path) -> Result<String, WorkspaceError> {
+    std::fs::read_to_string(path).map_err(|source| WorkspaceError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// The new `[workspace.package]` version of each workspace root `Cargo.toml` whose members
+/// inherit their version, adding roots which aren't already in `manifests`.
+#[cfg(feature = "toml")]
+fn workspace_versions(
+    manifests: &mut Vec<(PathBuf, String)>,
+    versions: &BTreeMap<PackageName, Version>,
+) -> Result<BTreeMap<PathBuf, Version>, WorkspaceError> {
+    let parse = |path: &Path, contents: &str| {
+        DocumentMut::from_str(contents).map_err(|err| WorkspaceError::InvalidManifest {
+            path: path.to_path_buf(),
+            message: err.to_string(),
+        })
+    };
+    // The name and new version (if any) of each member inheriting the version of each root
+    let mut members: BTreeMap<PathBuf, Vec<(String, Option<&Version>)>> = BTreeMap::new();
+    let mut index = 0;
+    while let Some((path, contents)) = manifests.get(index) {
+        index += 1;
+        if path
+            .file_name()
+            .is_none_or(|file_name| file_name != "Cargo.toml")
+        {
+            continue;
+        }
+        let document = parse(path, contents)?;
+        let Some(package) = document.get("package").and_then(Item::as_table_like) else {
+            continue;
+        };
+        if !package.get("version").is_some_and(is_inherited) {
+            continue;
+        }
+        let name = package
+            .get("name")
+            .and_then(Item::as_str)
+            .unwrap_or_default();
+        let path = path.clone();
+        let mut root = None;
+        for dir in path.ancestors().skip(1) {
+            let candidate = dir.join("Cargo.toml");
+            let contents = match manifests
+                .iter()
+                .find(|(existing, _)| *existing == candidate)
+            {
+                Some((_, contents)) => contents.clone(),
+                None if candidate.is_file() => read_manifest(&candidate)?,
+                None => continue,
+            };
+            if parse(&candidate, &contents)?.contains_key("workspace") {
+                if !manifests.iter().any(|(existing, _)| *existing == candidate) {
+                    manifests.push((candidate.clone(), contents));
+                }
+                root = Some(candidate);
+                break;
+            }
+        }
+        let root = root.ok_or_else(|| WorkspaceError::InvalidManifest {
+            path: path.clone(),
+            message: "`version.workspace = true`, but no workspace root was found".to_string(),
+        })?;
+        members
+            .entry(root)
+            .or_default()
+            .push((name.to_string(), versions.get(name)));
+    }
+
+    let mut workspace_versions = BTreeMap::new();
+    for (root, members) in members {
+        let mut new_versions: Vec<Option<&Version>> =
+            members.iter().map(|(_, version)| *version).collect();
+        new_versions.dedup();
+        match new_versions.as_slice() {
+            [Some(version)] => {
+                workspace_versions.insert(root, (*version).clone());
+            }
+            [None] => {}
+            _ => {
+                let members: Vec<String> = members
+                    .iter()
+                    .map(|(name, version)| match version {
+                        Some(version) => format!("{name} {version}"),
+                        None => format!("{name} (not released)"),
+                    })
+                    .collect();
+                return Err(WorkspaceError::InvalidManifest {
+                    path: root,
+                    message: format!(
+                        "packages inheriting `workspace.package.version` must all get the same \
+                         version, but got {}",
+                        members.join(", ")
+                    ),
+                });
+            }
+        }
+    }
+    Ok(workspace_versions)
+}
+
+/// Whether a value in a `Cargo.toml` is inherited from the workspace, like
+/// `version.workspace = true`.
+#[cfg(feature = "toml")]
+fn is_inherited(item: &Item) -> bool {
+    item.as_table_like()
+        .and_then(|table| table.get("workspace"))
+        .and_then(Item::as_bool)
+        == Some(true)
+}
+
+impl Workspace {
+    /// Every manifest of the packages in this workspace which
+    /// [`ReleasePlan::update_manifests`] supports, including the `Cargo.lock` of Cargo packages.
+    #[must_use]
+    pub fn manifests(&self) -> Vec<PathBuf> {
+        let mut manifests = Vec::new();
+        for package in &self.packages {
+            manifests.extend(
+                ["Cargo.toml", "package.json", "pyproject.toml"]
+                    .into_iter()
+                    .map(|file_name| package.path.join(file_name))
+                    .filter(|path| path.is_file()),
+            );
+            if package.path.join("Cargo.toml").is_file() {
+                manifests.extend(
+                    package
+                        .path
+                        .ancestors()
+                        .map(|dir| dir.join("Cargo.lock"))
+                        .find(|path| path.is_file()),
+                );
+            }
+        }
+        manifests.sort();
+        manifests.dedup();
+        manifests
+    }
+}
+
+/// The new contents of the manifest at `path`.
+#[cfg_attr(not(all(feature = "json", feature = "toml")), allow(unused_variables))]
+fn updated_manifest(
+    path: &Path,
+    contents: &str,
+    versions: &BTreeMap<PackageName, Version>,
+    workspace_version: Option<&Version>,
+) -> Result<String, String> {
+    let file_name = path
+        .file_name()
+        .map(|file_name| file_name.to_string_lossy())
+        .unwrap_or_default();
+    match file_name.as_ref() {
+        #[cfg(feature = "json")]
+        "package.json" => updated_package_json(contents, versions),
+        #[cfg(not(feature = "json"))]
+        "package.json" => Err("updating package.json requires the `json` feature".to_string()),
+        #[cfg(feature = "toml")]
+        "Cargo.toml" => updated_toml(contents, |document| {
+            update_cargo_toml(document, versions, workspace_version);
+        }),
+        #[cfg(feature = "toml")]
+        "Cargo.lock" => updated_toml(contents, |document| {
+            update_cargo_lock(document, versions);
+        }),
+        #[cfg(feature = "toml")]
+        "pyproject.toml" => updated_toml(contents, |document| {
+            update_pyproject_toml(document, versions);
+        }),
+        #[cfg(not(feature = "toml"))]
+        "Cargo.toml" | "Cargo.lock" | "pyproject.toml" => {
+            Err(format!("updating {file_name} requires the `toml` feature"))
+        }
+        _ => Err(
+            "unsupported manifest, expected Cargo.toml, Cargo.lock, package.json, or \
+             pyproject.toml"
+                .to_string(),
+        ),
+    }
+}
+
+/// Parse a TOML manifest, `update` it, and write it back.
+#[cfg(feature = "toml")]
+fn updated_toml(contents: &str, update: impl FnOnce(&mut DocumentMut)) -> Result<String, String> {
+    let mut document = DocumentMut::from_str(contents).map_err(|err| err.to_string())?;
+    update(&mut document);
+    Ok(document.to_string())
+}
+
+#[cfg(feature = "toml")]
+fn update_cargo_toml(
+    document: &mut DocumentMut,
+    versions: &BTreeMap<PackageName, Version>,
+    workspace_version: Option<&Version>,
+) {
+    if let Some(package) = document
+        .get_mut("package")
+        .and_then(Item::as_table_like_mut)
+    {
+        update_package_version(package, versions, str::to_string);
+    }
+    if let Some(version) = workspace_version {
+        if let Some(value) = document
+            .get_mut("workspace")
+            .and_then(|workspace| workspace.get_mut("package"))
+            .and_then(|package| package.get_mut("version"))
+            .and_then(Item::as_value_mut)
+            .filter(|value| value.is_str())
+        {
+            set_string(value, &version.to_string());
+        }
+    }
+    let mut dependency_tables: Vec<&mut dyn TableLike> = Vec::new();
+    for (key, item) in document.iter_mut() {
+        let Some(table) = item.as_table_like_mut() else {
+            continue;
+        };
+        match key.get() {
+            "workspace" => dependency_tables.extend(
+                table
+                    .get_mut("dependencies")
+                    .and_then(Item::as_table_like_mut),
+            ),
+            "target" => {
+                for (_, target) in table.iter_mut() {
+                    let Some(target) = target.as_table_like_mut() else {
+                        continue;
+                    };
+                    for (key, item) in target.iter_mut() {
+                        if CARGO_DEPENDENCY_TABLES.contains(&key.get()) {
+                            dependency_tables.extend(item.as_table_like_mut());
+                        }
+                    }
+                }
+            }
+            key if CARGO_DEPENDENCY_TABLES.contains(&key) => dependency_tables.push(table),
+            _ => {}
+        }
+    }
+    for dependencies in dependency_tables {
+        for (key, dependency) in dependencies.iter_mut() {
+            let Some(dependency) = dependency.as_table_like_mut() else {
+                continue;
+            };
+            let name = dependency
+                .get("package")
+                .and_then(Item::as_str)
+                .unwrap_or(key.get());
+            let Some(version) = versions.get(name) else {
+                continue;
+            };
+            if !dependency.contains_key("path") {
+                continue;
+            }
+            if let Some(requirement) = dependency.get_mut("version") {
+                update_requirement(requirement, version);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "toml")]
+const CARGO_DEPENDENCY_TABLES: [&str; 3] =
+    ["dependencies", "dev-dependencies", "build-dependencies"];
+
+#[cfg(feature = "toml")]
+fn update_cargo_lock(document: &mut DocumentMut, versions: &BTreeMap<PackageName, Version>) {
+    let Some(packages) = document
+        .get_mut("package")
+        .and_then(Item::as_array_of_tables_mut)
+    else {
+        return;
+    };
+    for package in packages.iter_mut() {
+        // Packages from a registry or git have a `source`, local ones don't
+        if !package.contains_key("source") {
+            update_package_version(package, versions, str::to_string);
+        }
+    }
+}
+
+#[cfg(feature = "toml")]
+fn update_pyproject_toml(document: &mut DocumentMut, versions: &BTreeMap<PackageName, Version>) {
+    let versions: BTreeMap<PackageName, Version> = versions
+        .iter()
+        .map(|(name, version)| (normalize_python_name(name), version.clone()))
+        .collect();
+    if let Some(project) = document
+        .get_mut("project")
+        .and_then(Item::as_table_like_mut)
+    {
+        update_package_version(project, &versions, normalize_python_name);
+    }
+    if let Some(poetry) = document
+        .get_mut("tool")
+        .and_then(|tool| tool.get_mut("poetry"))
+        .and_then(Item::as_table_like_mut)
+    {
+        update_package_version(poetry, &versions, normalize_python_name);
+    }
+}
+
+/// The name of a Python package as it's compared by package indexes (see
+/// [PEP 503](https://peps.python.org/pep-0503/#normalized-names)).
+#[cfg(feature = "toml")]
+fn normalize_python_name(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    for c in name.chars() {
+        if matches!(c, '-' | '_' | '.') {
+            if !normalized.ends_with('-') {
+                normalized.push('-');
+            }
+        } else {
+            normalized.push(c.to_ascii_lowercase());
+        }
+    }
+    normalized
+}
+
+/// Set the `version` of a table with a `name` (compared using `normalize`) in `versions`.
+#[cfg(feature = "toml")]
+fn update_package_version(
+    table: &mut dyn TableLike,
+    versions: &BTreeMap<PackageName, Version>,
+    normalize: fn(&str) -> String,
+) {
+    let Some(version) = table
+        .get("name")
+        .and_then(Item::as_str)
+        .and_then(|name| versions.get(&normalize(name)))
+    else {
+        return;
+    };
+    if let Some(value) = table
+        .get_mut("version")
+        .and_then(Item::as_value_mut)
+        .filter(|value| value.is_str())
+    {
+        set_string(value, &version.to_string());
+    }
+}
+
+/// Point a string requirement at `version`, see [`updated_requirement`].
+#[cfg(feature = "toml")]
+fn update_requirement(item: &mut Item, version: &Version) {
+    let Some(value) = item.as_value_mut() else {
+        return;
+    };
+    if let Some(requirement) = value
+        .as_str()
+        .and_then(|requirement| updated_requirement(requirement, version))
+    {
+        set_string(value, &requirement);
+    }
+}
+
+/// Replace a string value, keeping the whitespace and comments around it.
+#[cfg(feature = "toml")]
+fn set_string(value: &mut toml_edit::Value, new: &str) {
+    let decor = value.decor().clone();
+    *value = toml_edit::Value::from(new);
+    *value.decor_mut() = decor;
+}
+
+/// `requirement` changed to require `version` with the same operator (and `workspace:`
+/// protocol), or `None` if it's not a single version which can be changed.
+#[cfg(any(feature = "json", feature = "toml"))]
+fn updated_requirement(requirement: &str, version: &Version) -> Option<String> {
+    let (protocol, range) = requirement
+        .strip_prefix("workspace:")
+        .map_or(("", requirement), |range| ("workspace:", range));
+    let operator_end = range.find(|c: char| c.is_ascii_digit())?;
+    let (operator, old) = range.split_at(operator_end);
+    let operator = operator.trim_end_matches('v');
+    let is_single_version = ["", "=", "^", "~", ">="].contains(&operator)
+        && old
+            .split(['-', '+'])
+            .next()
+            .is_some_and(|core| core.split('.').all(|part| part.parse::<u64>().is_ok()))
+        && !old.contains(|c: char| c.is_whitespace() || matches!(c, ',' | '|' | '<'));
+    let updated = format!("{protocol}{operator}{version}");
+    (is_single_version && updated != requirement).then_some(updated)
+}
+
+/// The new contents of a `package.json`, only replacing the changed strings so that the rest of
+/// the formatting stays the same.
+#[cfg(feature = "json")]
+fn updated_package_json(
+    contents: &str,
+    versions: &BTreeMap<PackageName, Version>,
+) -> Result<String, String> {
+    let manifest: serde_json::Value =
+        serde_json::from_str(contents).map_err(|err| err.to_string())?;
+    let name = manifest.get("name").and_then(serde_json::Value::as_str);
+    let mut replacements: Vec<(Range<usize>, String)> = Vec::new();
+    for (path, span) in json_strings(contents) {
+        let old = contents
+            .get(span.clone())
+            .and_then(|raw| serde_json::from_str::<String>(raw).ok());
+        let new = match (path.as_slice(), old) {
+            ([key], Some(_)) if key == "version" => name
+                .and_then(|name| versions.get(name))
+                .map(ToString::to_string),
+            ([dependencies, dependency], Some(requirement))
+                if NPM_DEPENDENCY_OBJECTS.contains(&dependencies.as_str()) =>
+            {
+                versions
+                    .get(dependency)
+                    .and_then(|version| updated_requirement(&requirement, version))
+            }
+            _ => None,
+        };
+        if let Some(new) = new {
+            replacements.push((span, serde_json::Value::from(new).to_string()));
+        }
+    }
+    let mut updated = contents.to_string();
+    for (span, replacement) in replacements.into_iter().rev() {
+        updated.replace_range(span, &replacement);
+    }
+    Ok(updated)
+}
+
+#[cfg(feature = "json")]
+const NPM_DEPENDENCY_OBJECTS: [&str; 4] = [
+    "dependencies",
+    "devDependencies",
+    "peerDependencies",
+    "optionalDependencies",
+];
+
+/// Every string value (up to two objects deep) in a valid JSON document, with the keys leading to
+/// it and its span (including quotes), in order.
+#[cfg(feature = "json")]
+fn json_strings(contents: &str) -> Vec<(Vec<String>, Range<usize>)> {
+    let mut scanner = JsonScanner {
+        contents,
+        index: 0,
+        strings: Vec::new(),
+    };
+    scanner.value(&mut Vec::new());
+    scanner.strings
+}
+
+#[cfg(feature = "json")]
+struct JsonScanner<'a> {
+    contents: &'a str,
+    index: usize,
+    strings: Vec<(Vec<String>, Range<usize>)>,
+}
+
+#[cfg(feature = "json")]
+impl JsonScanner<'_> {
+    fn peek(&mut self) -> Option<u8> {
+        while let Some(byte) = self.contents.as_bytes().get(self.index) {
+            if !byte.is_ascii_whitespace() {
+                return Some(*byte);
+            }
+            self.index += 1;
+        }
+        None
+    }
+
+    fn value(&mut self, path: &mut Vec<String>) -> Option<()> {
+        match self.peek()? {
+            b'{' => {
+                self.index += 1;
+                while self.peek()? != b'}' {
+                    let key = self.string()?;
+                    let key = serde_json::from_str(self.contents.get(key)?).ok()?;
+                    if self.peek()? != b':' {
+                        return None;
+                    }
+                    self.index += 1;
+                    path.push(key);
+                    self.value(path);
+                    path.pop();
+                    if self.peek()? == b',' {
+                        self.index += 1;
+                    }
+                }
+                self.index += 1;
+            }
+            b'[' => {
+                self.index += 1;
+                path.push(String::new());
+                while self.peek()? != b']' {
+                    self.value(path)?;
+                    if self.peek()? == b',' {
+                        self.index += 1;
+                    }
+                }
+                path.pop();
+                self.index += 1;
+            }
+            b'"' => {
+                let span = self.string()?;
+                if path.len() <= 2 {
+                    self.strings.push((path.clone(), span));
+                }
+            }
+            _ => {
+                // A number, `true`, `false`, or `null`
+                while self
+                    .contents
+                    .as_bytes()
+                    .get(self.index)
+                    .is_some_and(|byte| !b",]} \t\r\n".contains(byte))
+                {
+                    self.index += 1;
+                }
+            }
+        }
+        Some(())
+    }
+
+    /// Skip past the string starting at the current position, returning its span.
+    fn string(&mut self) -> Option<Range<usize>> {
+        let start = self.index;
+        let mut escaped = false;
+        for (offset, byte) in self
+            .contents
+            .as_bytes()
+            .get(start + 1..)?
+            .iter()
+            .enumerate()
+        {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => {
+                    self.index = start + offset + 2;
+                    return Some(start..self.index);
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+}
+
+/// The changes needed to write new versions into manifests, created by
+/// [`ReleasePlan::update_manifests`].
+///
+/// Display this to show the changes as a diff (a dry run), or call [`ManifestPlan::execute`] to
+/// write them.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ManifestPlan {
+    pub updates: Vec<ManifestUpdate>,
+}
+
+impl ManifestPlan {
+    /// Write every [`ManifestUpdate`].
+    ///
+    /// # Errors
+    ///
+    /// If a manifest can't be written. Manifests which were already written are restored to
+    /// their original contents first, and any failures to do so are in
+    /// [`ManifestError::rollback_errors`].
+    pub fn execute(&self) -> Result<(), ManifestError> {
+        for (index, update) in self.updates.iter().enumerate() {
+            if let Err(source) = std::fs::write(&update.path, &update.updated) {
+                let rollback_errors = self
+                    .updates
+                    .iter()
+                    .take(index)
+                    .rev()
+                    .filter_map(|written| {
+                        std::fs::write(&written.path, &written.original)
+                            .err()
+                            .map(|err| (written.path.clone(), err))
+                    })
+                    .collect();
+                return Err(ManifestError {
+                    path: update.path.clone(),
+                    source,
+                    rollback_errors,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The error from [`ManifestPlan::execute`].
+#[derive(Debug)]
+pub struct ManifestError {
+    /// The manifest which couldn't be written.
+    pub path: PathBuf,
+    pub source: std::io::Error,
+    /// Any manifests which couldn't be restored to their original contents. If this is empty,
+    /// every manifest is back in its original state.
+    pub rollback_errors: Vec<(PathBuf, std::io::Error)>,
+}
+
+impl Display for ManifestError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "could not write {}: {}",
+            self.path.display(),
+            self.source
+        )?;
+        for (path, err) in &self.rollback_errors {
+            write!(f, "; could not restore {} either: {err}", path.display())?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ManifestError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Writes a unified diff of every update.
+impl Display for ManifestPlan {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for update in &self.updates {
+            write!(f, "{update}")?;
+        }
+        Ok(())
+    }
+}
+
+/// New contents for a single manifest in a [`ManifestPlan`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ManifestUpdate {
+    pub path: PathBuf,
+    /// The contents of the manifest when the plan was made.
+    pub original: String,
+    pub updated: String,
+}
+
+/// Writes a unified diff (without context lines) from the original to the updated contents.
+impl Display for ManifestUpdate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let original: Vec<&str> = self.original.lines().collect();
+        let updated: Vec<&str> = self.updated.lines().collect();
+        let prefix = original
+            .iter()
+            .zip(&updated)
+            .take_while(|(original, updated)| original == updated)
+            .count();
+        let suffix = original
+            .iter()
+            .rev()
+            .zip(updated.iter().rev())
+            .take(original.len().min(updated.len()) - prefix)
+            .take_while(|(original, updated)| original == updated)
+            .count();
+        let removed = original
+            .get(prefix..original.len() - suffix)
+            .unwrap_or_default();
+        let added = updated
+            .get(prefix..updated.len() - suffix)
+            .unwrap_or_default();
+        writeln!(f, "--- {}", self.path.display())?;
+        writeln!(f, "+++ {}", self.path.display())?;
+        let hunks: Vec<(usize, &[&str], &[&str])> = if removed.len() == added.len() {
+            // Only lines which changed, grouped when they're next to each other
+            let mut hunks: Vec<(usize, &[&str], &[&str])> = Vec::new();
+            let mut start = None;
+            for index in 0..=removed.len() {
+                let changed = removed.get(index) != added.get(index);
+                match (start, changed) {
+                    (None, true) => start = Some(index),
+                    (Some(first), false) => {
+                        hunks.push((
+                            prefix + first,
+                            removed.get(first..index).unwrap_or_default(),
+                            added.get(first..index).unwrap_or_default(),
+                        ));
+                        start = None;
+                    }
+                    _ => {}
+                }
+            }
+            hunks
+        } else {
+            vec![(prefix, removed, added)]
+        };
+        for (start, removed, added) in hunks {
+            writeln!(
+                f,
+                "@@ -{},{} +{},{} @@",
+                start + 1,
+                removed.len(),
+                start + 1,
+                added.len()
+            )?;
+            for line in removed {
+                writeln!(f, "-{line}")?;
+            }
+            for line in added {
+                writeln!(f, "+{line}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[cfg(any(feature = "json", feature = "toml"))]
+mod test_manifest {
+    use super::*;
+
+    #[test]
+    fn requirements() {
+        let version = Version::new(2, 0, 0);
+        let updated = |requirement| updated_requirement(requirement, &version);
+        assert_eq!(updated("1.2.3").as_deref(), Some("2.0.0"));
+        assert_eq!(updated("^1.2").as_deref(), Some("^2.0.0"));
+        assert_eq!(updated("=1.0.0-rc.1").as_deref(), Some("=2.0.0"));
+        assert_eq!(updated("~v1.2.3").as_deref(), Some("~2.0.0"));
+        assert_eq!(
+            updated("workspace:^1.0.0").as_deref(),
+            Some("workspace:^2.0.0")
+        );
+        assert_eq!(updated("2.0.0"), None);
+        assert_eq!(updated("*"), None);
+        assert_eq!(updated("workspace:^"), None);
+        assert_eq!(updated(">=1, <2"), None);
+        assert_eq!(updated("1.x"), None);
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn python_names() {
+        assert_eq!(normalize_python_name("My_Package.name"), "my-package-name");
+        assert_eq!(normalize_python_name("a--b"), "a-b");
+    }
+}
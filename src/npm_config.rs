@@ -0,0 +1,504 @@
+use std::{
+    collections::BTreeSet,
+    error::Error,
+    fmt::{Display, Formatter},
+    path::Path,
+    str::FromStr,
+};
+
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    de::{MapAccess, Visitor},
+    ser::SerializeMap,
+};
+use serde_json::Value;
+
+use crate::{Group, PackageName, Planner, Propagation};
+
+/// The settings from a `.changeset/config.json` file, as used by the original
+/// [changesets](https://github.com/changesets/changesets/blob/main/docs/config-file-options.md).
+///
+/// Only the options which affect release planning are interpreted, every other key is kept in
+/// [`NpmConfig::other`] so that it's written back unchanged. The top-level keys of a loaded file
+/// are written back in their original order, and options which weren't in the file are only
+/// written if they've been changed from their defaults.
+///
+/// Requires the `json` feature.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct NpmConfig {
+    /// Sets of packages which always share a version, see [`Group::Fixed`].
+    pub fixed: Vec<Vec<PackageName>>,
+    /// Sets of packages which share the largest bump, see [`Group::Linked`].
+    pub linked: Vec<Vec<PackageName>>,
+    /// Packages which should never be released.
+    pub ignore: Vec<PackageName>,
+    /// The branch that changes are compared against, `main` if not set.
+    pub base_branch: Option<String>,
+    /// Whether packages are published publicly, [`Access::Restricted`] if not set.
+    pub access: Access,
+    /// When dependents are released because of a dependency, [`UpdateInternalDependencies::Patch`]
+    /// if not set.
+    pub update_internal_dependencies: UpdateInternalDependencies,
+    /// Every key which isn't one of the other fields, in its original order. Only the order of
+    /// these top-level keys is kept, the keys of nested objects are sorted.
+    pub other: Vec<(String, Value)>,
+    /// Every top-level key of the loaded file (known options included), in their original order.
+    key_order: Vec<String>,
+}
+
+impl NpmConfig {
+    /// Load a `config.json` file.
+    ///
+    /// # Errors
+    ///
+    /// - If the file can't be read
+    /// - If the file isn't valid JSON
+    /// - If any of the known options have the wrong type or an unknown value
+    pub fn from_file<T: AsRef<Path>>(path: T) -> Result<Self, NpmConfigError> {
+        std::fs::read_to_string(path)?.parse()
+    }
+
+    /// Create a [`Planner`] which honors the settings in this config.
+    ///
+    /// [`UpdateInternalDependencies::Patch`] maps to [`Propagation::Patch`], and
+    /// [`UpdateInternalDependencies::Minor`] maps to [`Propagation::WhenRangeBroken`] (the closest
+    /// equivalent). Package names in `fixed`, `linked`, and `ignore` are used as-is, globs are
+    /// not expanded.
+    #[must_use]
+    pub fn planner(&self) -> Planner {
+        Planner {
+            groups: self
+                .fixed
+                .iter()
+                .cloned()
+                .map(Group::Fixed)
+                .chain(self.linked.iter().cloned().map(Group::Linked))
+                .collect(),
+            ignored: self.ignore.iter().cloned().collect::<BTreeSet<_>>(),
+            propagation: match self.update_internal_dependencies {
+                UpdateInternalDependencies::Patch => Propagation::Patch,
+                UpdateInternalDependencies::Minor => Propagation::WhenRangeBroken,
+            },
+            ..Planner::default()
+        }
+    }
+}
+
+impl FromStr for NpmConfig {
+    type Err = NpmConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let object = serde_json::from_str(s).map_err(|err| {
+            // Tell valid JSON of the wrong type apart from invalid JSON
+            match serde_json::from_str::<Value>(s) {
+                Ok(_) => NpmConfigError::NotAnObject,
+                Err(_) => NpmConfigError::Json(err),
+            }
+        })?;
+        Self::from_object(object)
+    }
+}
+
+/// A JSON object which keeps the order of its keys (unlike [`serde_json::Map`], which sorts
+/// them).
+struct Object(Vec<(String, Value)>);
+
+impl Object {
+    /// Remove a key, returning its value.
+    fn remove(&mut self, key: &str) -> Option<Value> {
+        let index = self.0.iter().position(|(existing, _)| existing == key)?;
+        Some(self.0.remove(index).1)
+    }
+
+    /// Set a key, replacing any existing value in place or adding it to the end.
+    fn insert(&mut self, key: &str, value: Value) {
+        if let Some((_, existing)) = self.0.iter_mut().find(|(existing, _)| existing == key) {
+            *existing = value;
+        } else {
+            self.0.push((key.to_string(), value));
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Object {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ObjectVisitor;
+
+        impl<'de> Visitor<'de> for ObjectVisitor {
+            type Value = Object;
+
+            fn expecting(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                f.write_str("a JSON object")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Object, A::Error> {
+                let mut object = Object(Vec::new());
+                while let Some((key, value)) = map.next_entry::<String, Value>()? {
+                    object.insert(&key, value);
+                }
+                Ok(object)
+            }
+        }
+
+        deserializer.deserialize_map(ObjectVisitor)
+    }
+}
+
+impl Serialize for Object {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (key, value) in &self.0 {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+impl NpmConfig {
+    fn from_object(mut other: Object) -> Result<Self, NpmConfigError> {
+        let mut config = Self {
+            key_order: other.0.iter().map(|(key, _)| key.clone()).collect(),
+            ..Self::default()
+        };
+        if let Some(value) = other.remove("fixed") {
+            config.fixed = package_groups("fixed", value)?;
+        }
+        if let Some(value) = other.remove("linked") {
+            config.linked = package_groups("linked", value)?;
+        }
+        if let Some(value) = other.remove("ignore") {
+            config.ignore = package_names(value).ok_or(NpmConfigError::InvalidOption {
+                key: "ignore",
+                expected: "an array of package names",
+            })?;
+        }
+        if let Some(value) = other.remove("baseBranch") {
+            let Value::String(base_branch) = value else {
+                return Err(NpmConfigError::InvalidOption {
+                    key: "baseBranch",
+                    expected: "a string",
+                });
+            };
+            config.base_branch = Some(base_branch);
+        }
+        if let Some(value) = other.remove("access") {
+            config.access = match value.as_str() {
+                Some("restricted") => Access::Restricted,
+                Some("public") => Access::Public,
+                _ => {
+                    return Err(NpmConfigError::InvalidOption {
+                        key: "access",
+                        expected: r#""restricted" or "public""#,
+                    });
+                }
+            };
+        }
+        if let Some(value) = other.remove("updateInternalDependencies") {
+            config.update_internal_dependencies = match value.as_str() {
+                Some("patch") => UpdateInternalDependencies::Patch,
+                Some("minor") => UpdateInternalDependencies::Minor,
+                _ => {
+                    return Err(NpmConfigError::InvalidOption {
+                        key: "updateInternalDependencies",
+                        expected: r#""patch" or "minor""#,
+                    });
+                }
+            };
+        }
+        config.other = other.0;
+        Ok(config)
+    }
+}
+
+fn package_names(value: Value) -> Option<Vec<PackageName>> {
+    let Value::Array(values) = value else {
+        return None;
+    };
+    values
+        .into_iter()
+        .map(|value| match value {
+            Value::String(name) => Some(name),
+            _ => None,
+        })
+        .collect()
+}
+
+fn package_groups(
+    key: &'static str,
+    value: Value,
+) -> Result<Vec<Vec<PackageName>>, NpmConfigError> {
+    let groups = match value {
+        Value::Array(groups) => groups.into_iter().map(package_names).collect(),
+        _ => None,
+    };
+    groups.ok_or(NpmConfigError::InvalidOption {
+        key,
+        expected: "an array of arrays of package names",
+    })
+}
+
+impl NpmConfig {
+    /// The config as a JSON object. The keys of the original file come first in their original
+    /// order, followed by any new keys in [`NpmConfig::other`] and then any known options which
+    /// were changed from their defaults.
+    fn to_object(&self) -> Object {
+        let groups = |groups: &Vec<Vec<PackageName>>| {
+            Value::Array(
+                groups
+                    .iter()
+                    .map(|group| group.iter().cloned().map(Value::String).collect())
+                    .collect(),
+            )
+        };
+        // Each known option in the order the original changesets uses, with whether it's the
+        // default (`None` if it has no value at all)
+        let known = [
+            ("fixed", Some(groups(&self.fixed)), self.fixed.is_empty()),
+            ("linked", Some(groups(&self.linked)), self.linked.is_empty()),
+            (
+                "access",
+                Some(Value::String(self.access.to_string())),
+                self.access == Access::default(),
+            ),
+            (
+                "baseBranch",
+                self.base_branch.clone().map(Value::String),
+                false,
+            ),
+            (
+                "updateInternalDependencies",
+                Some(Value::String(self.update_internal_dependencies.to_string())),
+                self.update_internal_dependencies == UpdateInternalDependencies::default(),
+            ),
+            (
+                "ignore",
+                Some(self.ignore.iter().cloned().map(Value::String).collect()),
+                self.ignore.is_empty(),
+            ),
+        ];
+        let in_file = |key: &str| self.key_order.iter().any(|existing| existing == key);
+
+        let mut object = Object(Vec::new());
+        for key in &self.key_order {
+            let value = match known.iter().find(|(known_key, ..)| known_key == key) {
+                Some((_, value, _)) => value.as_ref(),
+                None => self
+                    .other
+                    .iter()
+                    .find(|(other_key, _)| other_key == key)
+                    .map(|(_, value)| value),
+            };
+            if let Some(value) = value {
+                object.insert(key, value.clone());
+            }
+        }
+        for (key, value) in &self.other {
+            if !in_file(key) {
+                object.insert(key, value.clone());
+            }
+        }
+        for (key, value, is_default) in known {
+            if let (Some(value), false, false) = (value, is_default, in_file(key)) {
+                object.insert(key, value);
+            }
+        }
+        object
+    }
+}
+
+/// Writes the config as pretty-printed JSON, keeping the order of the keys in the loaded file.
+impl Display for NpmConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let json = serde_json::to_string_pretty(&self.to_object()).map_err(|_| std::fmt::Error)?;
+        writeln!(f, "{json}")
+    }
+}
+
+/// Serializes to the same object as a `config.json` file.
+impl Serialize for NpmConfig {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_object().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for NpmConfig {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::from_object(Object::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Whether packages are published publicly or privately.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Access {
+    #[default]
+    Restricted,
+    Public,
+}
+
+impl Display for Access {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Restricted => f.write_str("restricted"),
+            Self::Public => f.write_str("public"),
+        }
+    }
+}
+
+/// The smallest bump of a dependency which causes dependents to update it.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum UpdateInternalDependencies {
+    #[default]
+    Patch,
+    Minor,
+}
+
+impl Display for UpdateInternalDependencies {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Patch => f.write_str("patch"),
+            Self::Minor => f.write_str("minor"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum NpmConfigError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    /// The file was valid JSON, but not an object.
+    NotAnObject,
+    /// A known option had the wrong type or value.
+    InvalidOption {
+        key: &'static str,
+        expected: &'static str,
+    },
+}
+
+impl From<std::io::Error> for NpmConfigError {
+    fn from(err: std::io::Error) -> Self {
+        NpmConfigError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for NpmConfigError {
+    fn from(err: serde_json::Error) -> Self {
+        NpmConfigError::Json(err)
+    }
+}
+
+impl Display for NpmConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NpmConfigError::Io(err) => Display::fmt(err, f),
+            NpmConfigError::Json(err) => write!(f, "invalid JSON: {err}"),
+            NpmConfigError::NotAnObject => f.write_str("the config must be a JSON object"),
+            NpmConfigError::InvalidOption { key, expected } => {
+                write!(f, "`{key}` must be {expected}")
+            }
+        }
+    }
+}
+
+impl Error for NpmConfigError {}
+
+#[cfg(test)]
+mod test_npm_config {
+    use super::*;
+
+    const CONFIG: &str = r#"{
+  "$schema": "https://unpkg.com/@changesets/config@3.0.0/schema.json",
+  "changelog": "@changesets/cli/changelog",
+  "commit": false,
+  "fixed": [
+    [
+      "@scope/a",
+      "@scope/b"
+    ]
+  ],
+  "linked": [],
+  "access": "public",
+  "baseBranch": "main",
+  "updateInternalDependencies": "minor",
+  "ignore": [
+    "docs"
+  ]
+}
+"#;
+
+    #[test]
+    fn it_loads_known_options() {
+        let config = NpmConfig::from_str(CONFIG).unwrap();
+        assert_eq!(
+            config.fixed,
+            vec![vec!["@scope/a".to_string(), "@scope/b".to_string()]]
+        );
+        assert!(config.linked.is_empty());
+        assert_eq!(config.access, Access::Public);
+        assert_eq!(config.base_branch.as_deref(), Some("main"));
+        assert_eq!(
+            config.update_internal_dependencies,
+            UpdateInternalDependencies::Minor
+        );
+        assert_eq!(config.ignore, vec!["docs".to_string()]);
+        assert_eq!(
+            config
+                .other
+                .iter()
+                .map(|(key, _)| key.as_str())
+                .collect::<Vec<_>>(),
+            vec!["$schema", "changelog", "commit"]
+        );
+
+        let planner = config.planner();
+        assert_eq!(
+            planner.groups,
+            vec![Group::Fixed(vec!["@scope/a".into(), "@scope/b".into()])]
+        );
+        assert_eq!(planner.propagation, Propagation::WhenRangeBroken);
+        assert!(planner.ignored.contains("docs"));
+    }
+
+    #[test]
+    fn it_round_trips() {
+        let config = NpmConfig::from_str(CONFIG).unwrap();
+        assert_eq!(config.to_string(), CONFIG);
+    }
+
+    #[test]
+    fn it_only_writes_new_options_when_changed() {
+        let original = "{\n  \"baseBranch\": \"main\",\n  \"commit\": false\n}\n";
+        let mut config = NpmConfig::from_str(original).unwrap();
+        assert_eq!(config.to_string(), original);
+
+        config.ignore.push("docs".into());
+        config.base_branch = None;
+        config
+            .other
+            .push(("privatePackages".into(), Value::Bool(false)));
+        assert_eq!(
+            config.to_string(),
+            "{\n  \"commit\": false,\n  \"privatePackages\": false,\n  \"ignore\": [\n    \"docs\"\n  ]\n}\n"
+        );
+        assert_eq!(NpmConfig::default().to_string(), "{}\n");
+    }
+
+    #[test]
+    fn it_reports_invalid_options() {
+        let err = NpmConfig::from_str(r#"{"fixed": ["a", "b"]}"#).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`fixed` must be an array of arrays of package names"
+        );
+        let err = NpmConfig::from_str(r#"{"access": "private"}"#).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            r#"`access` must be "restricted" or "public""#
+        );
+        let err = NpmConfig::from_str("[]").unwrap_err();
+        assert_eq!(err.to_string(), "the config must be a JSON object");
+    }
+}
@@ -0,0 +1,250 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use crate::{
+    BumpRules, ChangeSet, ChangeType, PackageChange, PackageName, Release, UniqueId, Version,
+    VersionReq,
+};
+
+/// Options for deciding which [`Release`]s to create from a [`ChangeSet`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Planner {
+    /// Only release these packages, leaving changes for any other packages pending.
+    ///
+    /// If `None`, every package with changes is released. Packages released because of
+    /// [`Planner::dependencies`] are always included.
+    pub packages: Option<BTreeSet<PackageName>>,
+    /// Never release these packages, even if they have changes, depend on a released package, or
+    /// are part of a [`Group`].
+    pub ignored: BTreeSet<PackageName>,
+    /// The internal dependencies of each package. When a dependency is released, the dependent
+    /// package may also need to be released, depending on [`Planner::propagation`].
+    pub dependencies: BTreeMap<PackageName, Vec<Dependency>>,
+    pub propagation: Propagation,
+    /// The current version of each package, used to determine new versions for
+    /// [`Propagation::WhenRangeBroken`] and to describe propagated changes.
+    pub versions: BTreeMap<PackageName, Version>,
+    pub rules: BumpRules,
+    /// Packages which are released together, see [`Group`].
+    pub groups: Vec<Group>,
+    /// Leave out packages whose changes are all [`ChangeType::None`], since their version wouldn't
+    /// change. Their changes stay pending until the package is released for another reason.
+    pub skip_none_only: bool,
+}
+
+impl Planner {
+    /// Decide which [`Release`]s to make from a [`ChangeSet`].
+    ///
+    /// Packages which depend on a released package get a synthetic [`PackageChange`] explaining
+    /// that the dependency was updated (see [`Propagation`]). This continues transitively, so a
+    /// package depending on a package which depends on a released package may also be released.
+    ///
+    /// The [`Release::minimum_change_type`] of packages in [`Planner::groups`] is raised to match
+    /// the largest bump in their group.
+    ///
+    /// A package left out by [`Planner::packages`] which is still released because of a
+    /// dependency or a [`Group::Fixed`] includes all of its pending changes, so they're never
+    /// released under a smaller bump than they require.
+    ///
+    /// Pass the result to [`ChangeSet::consume`] once the releases are made to remove (only)
+    /// the parts of change files which were released.
+    #[must_use]
+    pub fn plan(&self, changeset: &ChangeSet) -> Vec<Release> {
+        let mut releases: Vec<Release> = changeset
+            .releases()
+            .iter()
+            .filter(|release| {
+                self.packages
+                    .as_ref()
+                    .is_none_or(|packages| packages.contains(&release.package_name))
+                    && !self.ignored.contains(&release.package_name)
+            })
+            .cloned()
+            .collect();
+        // Both steps can affect each other, so repeat them (without short-circuiting) until
+        // neither makes any more changes.
+        while self.propagate(changeset, &mut releases) | self.apply_groups(changeset, &mut releases)
+        {
+        }
+        if self.skip_none_only {
+            releases.retain(|release| release.bump(&self.rules).is_some());
+        }
+        releases
+    }
+
+    /// The release of a package which wasn't selected for release but is pulled in by a
+    /// dependency or group, including any changes it already has pending in `changeset`.
+    fn pulled_in(changeset: &ChangeSet, package_name: &PackageName) -> Release {
+        changeset
+            .releases()
+            .iter()
+            .find(|release| &release.package_name == package_name)
+            .cloned()
+            .unwrap_or_else(|| Release {
+                package_name: package_name.clone(),
+                changes: Vec::new(),
+                minimum_change_type: None,
+            })
+    }
+
+    /// Raise the change type of every grouped package to the largest in its group, returning
+    /// whether anything changed.
+    fn apply_groups(&self, changeset: &ChangeSet, releases: &mut Vec<Release>) -> bool {
+        let mut changed = false;
+        for group in &self.groups {
+            let (Group::Fixed(members) | Group::Linked(members)) = group;
+            let Some(bump) = releases
+                .iter()
+                .filter(|release| members.contains(&release.package_name))
+                .filter_map(|release| release.bump(&self.rules))
+                .max()
+            else {
+                continue;
+            };
+            for member in members {
+                if let Some(release) = releases
+                    .iter_mut()
+                    .find(|release| &release.package_name == member)
+                {
+                    if release.bump(&self.rules) < Some(bump) {
+                        release.minimum_change_type = Some(bump.into());
+                        changed = true;
+                    }
+                } else if matches!(group, Group::Fixed(_)) && !self.ignored.contains(member) {
+                    let mut release = Self::pulled_in(changeset, member);
+                    if release.bump(&self.rules) < Some(bump) {
+                        release.minimum_change_type = Some(bump.into());
+                    }
+                    releases.push(release);
+                    changed = true;
+                }
+            }
+        }
+        changed
+    }
+
+    /// Add or raise one round of propagated changes, returning whether anything changed.
+    fn propagate(&self, changeset: &ChangeSet, releases: &mut Vec<Release>) -> bool {
+        let mut changed = false;
+        for (package_name, dependencies) in &self.dependencies {
+            if self.ignored.contains(package_name) {
+                continue;
+            }
+            for dependency in dependencies {
+                let Some(change) = releases
+                    .iter()
+                    .find(|release| release.package_name == dependency.name)
+                    .and_then(|release| self.propagated_change(dependency, release))
+                else {
+                    continue;
+                };
+                let release = if let Some(index) = releases
+                    .iter()
+                    .position(|release| &release.package_name == package_name)
+                {
+                    releases.get_mut(index)
+                } else {
+                    releases.push(Self::pulled_in(changeset, package_name));
+                    releases.last_mut()
+                };
+                let Some(release) = release else {
+                    continue;
+                };
+                if let Some(existing) = release
+                    .changes
+                    .iter_mut()
+                    .find(|existing| existing.unique_id == change.unique_id)
+                {
+                    if existing.change_type <= change.change_type && *existing != change {
+                        *existing = change;
+                        changed = true;
+                    }
+                } else {
+                    release.changes.push(change);
+                    changed = true;
+                }
+            }
+        }
+        changed
+    }
+
+    /// The change a dependent package needs because `dependency` is being released, if any.
+    fn propagated_change(
+        &self,
+        dependency: &Dependency,
+        release: &Release,
+    ) -> Option<PackageChange> {
+        // Dependents don't need updating if the version of the dependency isn't changing
+        let bump = release.bump(&self.rules)?;
+        let new_version = self
+            .versions
+            .get(&dependency.name)
+            .and_then(|current| release.next_version(current, &self.rules));
+        let change_type = match self.propagation {
+            Propagation::Patch => ChangeType::Patch,
+            Propagation::MatchBump => bump.into(),
+            Propagation::WhenRangeBroken => {
+                if new_version
+                    .as_ref()
+                    .is_some_and(|version| dependency.requirement.matches(version))
+                {
+                    return None;
+                }
+                ChangeType::Patch
+            }
+        };
+        let summary = match new_version {
+            Some(version) => format!("Updated dependency `{}` to {version}", dependency.name),
+            None => format!("Updated dependency `{}`", dependency.name),
+        };
+        Some(PackageChange {
+            unique_id: Arc::new(UniqueId::exact(format!("dependency:{}", dependency.name))),
+            change_type,
+            summary: summary.into(),
+            provenance: None,
+        })
+    }
+}
+
+/// A package that another package depends on.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Dependency {
+    pub name: PackageName,
+    /// The versions of the dependency which the dependent package accepts.
+    pub requirement: VersionReq,
+}
+
+/// How a release of a dependency affects the packages which depend on it.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Propagation {
+    /// Always release dependents with a [`ChangeType::Patch`].
+    #[default]
+    Patch,
+    /// Release dependents with the same [`crate::Bump`] as the dependency.
+    MatchBump,
+    /// Only release dependents (with a [`ChangeType::Patch`]) when the new version of the
+    /// dependency doesn't match [`Dependency::requirement`]. If the current version of the
+    /// dependency isn't in [`Planner::versions`], dependents are always released.
+    WhenRangeBroken,
+}
+
+/// A set of packages which are released together, like the `fixed` and `linked` options of the
+/// original [changesets](https://github.com/changesets/changesets/blob/main/docs/config-file-options.md).
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Group {
+    /// Every package in the group is released whenever any of them are, all with the largest
+    /// bump. Packages in a fixed group are expected to share a single version.
+    Fixed(Vec<PackageName>),
+    /// Packages in the group which are released all get the largest bump of the group, but
+    /// packages without changes are not released.
+    Linked(Vec<PackageName>),
+}
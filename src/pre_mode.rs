@@ -0,0 +1,116 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{BumpRules, PackageChange, PackageName, Prerelease, Release, UniqueId, Version};
+
+/// Tracks a series of pre-releases (like `2.0.0-rc.0`, `2.0.0-rc.1`, ...) leading up to a stable
+/// release, similar to the `pre.json` file of the original [changesets].
+///
+/// Change files are _not_ removed when a pre-release is made, because every change needs to be
+/// included when the final stable version is released. Instead, this records which changes have
+/// already been released so that each pre-release only documents what's new.
+///
+/// [changesets]: https://github.com/changesets/changesets/blob/main/docs/prereleases.md
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PreMode {
+    /// The label of the pre-releases, like `rc` or `beta`.
+    pub tag: String,
+    /// The version of each package before its first pre-release.
+    pub initial_versions: BTreeMap<PackageName, Version>,
+    /// Every [`UniqueId`] which has already been included in a pre-release of each package.
+    pub released: BTreeMap<PackageName, BTreeSet<UniqueId>>,
+}
+
+impl PreMode {
+    /// Enter pre-release mode, where every release will be a pre-release like `{tag}.{number}`
+    /// (see [`Prerelease::new`]).
+    #[must_use]
+    pub fn new<T: Into<String>>(tag: T) -> Self {
+        Self {
+            tag: tag.into(),
+            initial_versions: BTreeMap::new(),
+            released: BTreeMap::new(),
+        }
+    }
+
+    /// The next pre-release version of a package given its `current` version.
+    ///
+    /// The stable part is calculated by applying every change in the [`Release`] (including ones
+    /// which were already pre-released) to the package's initial version. If `current` is
+    /// already a pre-release of that same version with the same tag and a number (see
+    /// [`Prerelease::numbered`]), the number is incremented. Otherwise, it starts at `0`.
+    ///
+    /// If nothing in the [`Release`] requires a bump (see [`Release::bump`]), `current` is
+    /// returned unchanged. Returns `None` if the bump would overflow (see [`Version::bump`]).
+    #[must_use]
+    pub fn next_version(
+        &self,
+        release: &Release,
+        current: &Version,
+        rules: &BumpRules,
+    ) -> Option<Version> {
+        if release.bump(rules).is_none() {
+            return Some(current.clone());
+        }
+        let target = self.graduate(release, current, rules)?;
+        let number = match current.pre.as_ref().and_then(Prerelease::numbered) {
+            Some((label, number)) if label == self.tag && current.stable() == target => {
+                number.saturating_add(1)
+            }
+            _ => 0,
+        };
+        Some(Version {
+            pre: Some(Prerelease::new(&self.tag, number)),
+            ..target
+        })
+    }
+
+    /// The stable version a package should be released as when leaving pre-release mode.
+    ///
+    /// This includes every change in the [`Release`], whether it was already pre-released or not.
+    /// Returns `None` if the bump would overflow (see [`Version::bump`]).
+    #[must_use]
+    pub fn graduate(
+        &self,
+        release: &Release,
+        current: &Version,
+        rules: &BumpRules,
+    ) -> Option<Version> {
+        let initial = self
+            .initial_versions
+            .get(&release.package_name)
+            .unwrap_or(current);
+        release
+            .next_version(initial, rules)
+            .map(|version| version.stable())
+    }
+
+    /// The changes in a [`Release`] which have not been included in any previous pre-release of
+    /// that package.
+    pub fn unreleased_changes<'release>(
+        &self,
+        release: &'release Release,
+    ) -> impl Iterator<Item = &'release PackageChange> {
+        let released = self.released.get(&release.package_name);
+        release.changes.iter().filter(move |change| {
+            released.is_none_or(|released| !released.contains(change.unique_id.as_ref()))
+        })
+    }
+
+    /// Record that a pre-release of `release` was made, where `current` was the package's version
+    /// before that pre-release.
+    pub fn record(&mut self, release: &Release, current: &Version) {
+        self.initial_versions
+            .entry(release.package_name.clone())
+            .or_insert_with(|| current.clone());
+        self.released
+            .entry(release.package_name.clone())
+            .or_default()
+            .extend(
+                release
+                    .changes
+                    .iter()
+                    .map(|change| change.unique_id.as_ref().clone()),
+            );
+    }
+}
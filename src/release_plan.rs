@@ -0,0 +1,299 @@
+#[cfg(feature = "json")]
+use std::fmt::{Display, Formatter};
+
+use crate::{Bump, ChangeSet, PackageName, Planner, UniqueId, Version};
+
+/// A summary of what will be released, which can be written as the same JSON as
+/// `changeset status --output` from the original
+/// [changesets](https://github.com/changesets/changesets/tree/main/packages/cli#status).
+///
+/// Create one with [`Planner::release_plan`].
+///
+/// `changeset status --output` always has an `oldVersion` and `newVersion`, so releases without
+/// both (see [`PlannedRelease::new_version`]) are left out of that JSON.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReleasePlan {
+    /// Every change which contributes to at least one of the [`ReleasePlan::releases`], ordered
+    /// by ID.
+    pub changes: Vec<PlannedChange>,
+    pub releases: Vec<PlannedRelease>,
+}
+
+/// A change file in a [`ReleasePlan`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlannedChange {
+    pub unique_id: UniqueId,
+    pub summary: String,
+    /// Every package in the change file with the [`Bump`] it requires (`None` for
+    /// [`crate::ChangeType::None`]).
+    pub packages: Vec<(PackageName, Option<Bump>)>,
+}
+
+/// A package in a [`ReleasePlan`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlannedRelease {
+    pub package_name: PackageName,
+    /// The bump of the release, `None` if no change requires one.
+    pub bump: Option<Bump>,
+    /// The current version, if it's in [`Planner::versions`].
+    pub old_version: Option<Version>,
+    /// The version after the release, if the current version is known and bumping it doesn't
+    /// overflow (see [`Version::bump`]).
+    pub new_version: Option<Version>,
+    /// The changes included in the release. Changes which [`Planner`] added because of a
+    /// dependency aren't included, since they don't have a change file.
+    pub changes: Vec<UniqueId>,
+}
+
+impl Planner {
+    /// Plan the releases for a [`ChangeSet`] (see [`Planner::plan`]) and summarize them as a
+    /// [`ReleasePlan`].
+    #[must_use]
+    pub fn release_plan(&self, changeset: &ChangeSet) -> ReleasePlan {
+        let has_file = |unique_id: &UniqueId| {
+            changeset.releases().iter().any(|release| {
+                release
+                    .changes
+                    .iter()
+                    .any(|change| change.unique_id.as_ref() == unique_id)
+            })
+        };
+        let releases: Vec<PlannedRelease> = self
+            .plan(changeset)
+            .into_iter()
+            .map(|release| {
+                let old_version = self.versions.get(&release.package_name).cloned();
+                PlannedRelease {
+                    bump: release.bump(&self.rules),
+                    new_version: old_version
+                        .as_ref()
+                        .and_then(|current| release.next_version(current, &self.rules)),
+                    old_version,
+                    changes: release
+                        .changes
+                        .iter()
+                        .map(|change| change.unique_id.as_ref())
+                        .filter(|unique_id| has_file(unique_id))
+                        .cloned()
+                        .collect(),
+                    package_name: release.package_name,
+                }
+            })
+            .collect();
+
+        let mut unique_ids: Vec<&UniqueId> = releases
+            .iter()
+            .flat_map(|release| &release.changes)
+            .collect();
+        unique_ids.sort();
+        unique_ids.dedup();
+        let changes = unique_ids
+            .into_iter()
+            .map(|unique_id| {
+                let mut summary = String::new();
+                let packages = changeset
+                    .releases()
+                    .iter()
+                    .filter_map(|release| {
+                        let change = release
+                            .changes
+                            .iter()
+                            .find(|change| change.unique_id.as_ref() == unique_id)?;
+                        summary = change.summary.to_string();
+                        Some((
+                            release.package_name.clone(),
+                            self.rules.bump_for(&change.change_type),
+                        ))
+                    })
+                    .collect();
+                PlannedChange {
+                    unique_id: unique_id.clone(),
+                    summary,
+                    packages,
+                }
+            })
+            .collect();
+        ReleasePlan { changes, releases }
+    }
+}
+
+/// The plan in the format of `changeset status --output`, with its fields in the same order.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Status {
+    changesets: Vec<StatusChangeset>,
+    releases: Vec<StatusRelease>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct StatusChangeset {
+    releases: Vec<StatusPackage>,
+    summary: String,
+    id: UniqueId,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct StatusPackage {
+    name: PackageName,
+    #[serde(rename = "type", with = "bump_type")]
+    bump: Option<Bump>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StatusRelease {
+    name: PackageName,
+    #[serde(rename = "type", with = "bump_type")]
+    bump: Option<Bump>,
+    old_version: Version,
+    changesets: Vec<UniqueId>,
+    new_version: Version,
+}
+
+#[cfg(feature = "serde")]
+impl From<&ReleasePlan> for Status {
+    fn from(plan: &ReleasePlan) -> Self {
+        Self {
+            changesets: plan
+                .changes
+                .iter()
+                .map(|change| StatusChangeset {
+                    releases: change
+                        .packages
+                        .iter()
+                        .map(|(name, bump)| StatusPackage {
+                            name: name.clone(),
+                            bump: *bump,
+                        })
+                        .collect(),
+                    summary: change.summary.clone(),
+                    id: change.unique_id.clone(),
+                })
+                .collect(),
+            releases: plan
+                .releases
+                .iter()
+                .filter_map(|release| {
+                    Some(StatusRelease {
+                        name: release.package_name.clone(),
+                        bump: release.bump,
+                        old_version: release.old_version.clone()?,
+                        changesets: release.changes.clone(),
+                        new_version: release.new_version.clone()?,
+                    })
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl ReleasePlan {
+    /// The plan in the format of `changeset status --output`.
+    ///
+    /// Releases without a known old and new version are left out, and releases without a bump
+    /// have the type `"none"`.
+    ///
+    /// Requires the `json` feature, which also enables writing the plan with [`Display`].
+    ///
+    /// # Errors
+    ///
+    /// If the plan can't be serialized, which shouldn't happen since every key is a string.
+    pub fn to_json(&self) -> serde_json::Result<serde_json::Value> {
+        serde_json::to_value(Status::from(self))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<Status> for ReleasePlan {
+    fn from(status: Status) -> Self {
+        Self {
+            changes: status
+                .changesets
+                .into_iter()
+                .map(|changeset| PlannedChange {
+                    unique_id: changeset.id,
+                    summary: changeset.summary,
+                    packages: changeset
+                        .releases
+                        .into_iter()
+                        .map(|package| (package.name, package.bump))
+                        .collect(),
+                })
+                .collect(),
+            releases: status
+                .releases
+                .into_iter()
+                .map(|release| PlannedRelease {
+                    package_name: release.name,
+                    bump: release.bump,
+                    old_version: Some(release.old_version),
+                    new_version: Some(release.new_version),
+                    changes: release.changesets,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The `type` of a release or change in `changeset status --output`, which is `"none"` instead
+/// of `null` when there's no [`Bump`].
+#[cfg(feature = "serde")]
+mod bump_type {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer, de::IntoDeserializer};
+
+    use crate::Bump;
+
+    // `serde(with)` always passes a reference
+    #[allow(clippy::ref_option, clippy::trivially_copy_pass_by_ref)]
+    pub(super) fn serialize<S: Serializer>(
+        bump: &Option<Bump>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match bump {
+            Some(bump) => bump.serialize(serializer),
+            None => serializer.serialize_str("none"),
+        }
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Bump>, D::Error> {
+        let bump = String::deserialize(deserializer)?;
+        if bump == "none" {
+            return Ok(None);
+        }
+        Bump::deserialize(bump.into_deserializer()).map(Some)
+    }
+}
+
+/// Writes the plan as pretty-printed JSON, exactly like `changeset status --output`.
+#[cfg(feature = "json")]
+impl Display for ReleasePlan {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let json =
+            serde_json::to_string_pretty(&Status::from(self)).map_err(|_| std::fmt::Error)?;
+        f.write_str(&json)
+    }
+}
+
+/// Serializes to the same object as `changeset status --output`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for ReleasePlan {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Status::from(self).serialize(serializer)
+    }
+}
+
+/// Deserializes from the output of `changeset status --output`.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ReleasePlan {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Status::deserialize(deserializer).map(Self::from)
+    }
+}
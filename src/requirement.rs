@@ -0,0 +1,325 @@
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+use crate::{ParseVersionError, Prerelease, Version};
+
+/// A range of acceptable versions for a dependency, like `^1.2.3` or `>=1.0, <2.0`.
+///
+/// Comparators are separated by `,` or whitespace (all must match), and alternatives by `||`
+/// (any may match). The supported operators are `=`, `>`, `>=`, `<`, `<=`, `~`, `^`, and the
+/// wildcards `*`, `x`, and `X`. A version without an operator is treated like `^` (as Cargo does).
+/// Hyphen ranges like `1.2.3 - 2.0` (as in npm) include both ends, so they're the same as
+/// `>=1.2.3, <=2.0`.
+///
+/// As in Cargo and npm, a pre-release version only matches if one of the comparators it's checked
+/// against has a pre-release of the same `major.minor.patch`. So `>=1.0.0-alpha` matches
+/// `1.0.0-beta`, but `^1.2.3` doesn't match `2.0.0-rc.0` (or `1.5.0-rc.0`).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VersionReq {
+    raw: String,
+    alternatives: Vec<Vec<Comparator>>,
+}
+
+impl VersionReq {
+    /// Whether `version` is within this range.
+    #[must_use]
+    pub fn matches(&self, version: &Version) -> bool {
+        self.alternatives.iter().any(|comparators| {
+            comparators
+                .iter()
+                .all(|comparator| comparator.matches(version))
+                && (!version.is_prerelease()
+                    || comparators
+                        .iter()
+                        .any(|comparator| comparator.allows_prerelease_of(version)))
+        })
+    }
+}
+
+impl FromStr for VersionReq {
+    type Err = ParseVersionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let alternatives = s
+            .split("||")
+            .map(|alternative| {
+                let mut comparators = Vec::new();
+                let mut pending_op = None;
+                let mut tokens = alternative
+                    .split(|c: char| c == ',' || c.is_whitespace())
+                    .filter(|token| !token.is_empty())
+                    .peekable();
+                while let Some(token) = tokens.next() {
+                    let token = match pending_op.take() {
+                        Some(op) => format!("{op}{token}"),
+                        None => token.to_string(),
+                    };
+                    if token.chars().all(|c| "=<>~^".contains(c)) {
+                        pending_op = Some(token);
+                        continue;
+                    }
+                    if tokens.next_if_eq(&"-").is_some() {
+                        // A hyphen range, like `1.2.3 - 2.0`, includes both ends
+                        let upper = tokens.next().ok_or_else(|| {
+                            ParseVersionError::InvalidRequirement(alternative.trim().to_string())
+                        })?;
+                        comparators.push(Comparator::from_str(&format!(">={token}"))?);
+                        comparators.push(Comparator::from_str(&format!("<={upper}"))?);
+                        continue;
+                    }
+                    comparators.push(Comparator::from_str(&token)?);
+                }
+                if let Some(op) = pending_op {
+                    return Err(ParseVersionError::InvalidRequirement(op));
+                }
+                Ok(comparators)
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            raw: s.trim().to_string(),
+            alternatives,
+        })
+    }
+}
+
+impl Display for VersionReq {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+#[cfg(feature = "serde")]
+serde_as_string!(VersionReq);
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Op {
+    Exact,
+    Greater,
+    GreaterEq,
+    Less,
+    LessEq,
+    Tilde,
+    Caret,
+}
+
+/// A single operator and (possibly partial) version, like `>=1.2`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Comparator {
+    op: Op,
+    major: Option<u64>,
+    minor: Option<u64>,
+    patch: Option<u64>,
+    pre: Option<Prerelease>,
+}
+
+impl Comparator {
+    /// Whether this has a pre-release of the same `major.minor.patch` as `version`.
+    fn allows_prerelease_of(&self, version: &Version) -> bool {
+        self.pre.is_some()
+            && self.major == Some(version.major)
+            && self.minor == Some(version.minor)
+            && self.patch == Some(version.patch)
+    }
+
+    fn matches(&self, version: &Version) -> bool {
+        let Some(major) = self.major else {
+            return true;
+        };
+        let lower = Version {
+            major,
+            minor: self.minor.unwrap_or(0),
+            patch: self.patch.unwrap_or(0),
+            pre: self.pre.clone(),
+            build: None,
+        };
+        // The first version that's no longer covered by a partial version, so `1.2` is `1.3.0`.
+        // `None` if there is no such version, because a component is already `u64::MAX`.
+        let after_partial = match (self.minor, self.patch) {
+            (None, _) => major.checked_add(1).map(|major| Version::new(major, 0, 0)),
+            (Some(minor), None) => minor
+                .checked_add(1)
+                .map(|minor| Version::new(major, minor, 0)),
+            (Some(minor), Some(patch)) => patch
+                .checked_add(1)
+                .map(|patch| Version::new(major, minor, patch)),
+        };
+        let below = |upper: Option<Version>| upper.is_none_or(|upper| *version < upper);
+        let is_partial = self.patch.is_none();
+        match self.op {
+            Op::Exact if is_partial => *version >= lower && below(after_partial),
+            Op::Exact => *version == lower,
+            Op::Greater if is_partial => !below(after_partial),
+            Op::Greater => *version > lower,
+            Op::GreaterEq => *version >= lower,
+            Op::Less => *version < lower,
+            Op::LessEq if is_partial => below(after_partial),
+            Op::LessEq => *version <= lower,
+            Op::Tilde => {
+                let upper = match self.minor {
+                    Some(minor) => minor
+                        .checked_add(1)
+                        .map(|minor| Version::new(major, minor, 0)),
+                    None => major.checked_add(1).map(|major| Version::new(major, 0, 0)),
+                };
+                *version >= lower && below(upper)
+            }
+            Op::Caret => {
+                let upper = match (major, self.minor, self.patch) {
+                    (0, Some(0), Some(patch)) => {
+                        patch.checked_add(1).map(|patch| Version::new(0, 0, patch))
+                    }
+                    (0, Some(minor), _) => {
+                        minor.checked_add(1).map(|minor| Version::new(0, minor, 0))
+                    }
+                    _ => major.checked_add(1).map(|major| Version::new(major, 0, 0)),
+                };
+                *version >= lower && below(upper)
+            }
+        }
+    }
+}
+
+impl FromStr for Comparator {
+    type Err = ParseVersionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (op, rest) = [
+            (">=", Op::GreaterEq),
+            ("<=", Op::LessEq),
+            (">", Op::Greater),
+            ("<", Op::Less),
+            ("=", Op::Exact),
+            ("~", Op::Tilde),
+            ("^", Op::Caret),
+        ]
+        .into_iter()
+        .find_map(|(prefix, op)| s.strip_prefix(prefix).map(|rest| (op, rest)))
+        .unwrap_or((Op::Caret, s));
+        let rest = rest.trim().strip_prefix('v').unwrap_or(rest.trim());
+        // Build metadata doesn't affect which versions match
+        let rest = rest.split_once('+').map_or(rest, |(rest, _)| rest);
+        let (stable, pre) = match rest.split_once('-') {
+            Some((stable, pre)) => (stable, Some(Prerelease::from_str(pre)?)),
+            None => (rest, None),
+        };
+        let mut components = stable.split('.').map(|component| {
+            if matches!(component, "*" | "x" | "X") {
+                Ok(None)
+            } else {
+                component
+                    .parse::<u64>()
+                    .map(Some)
+                    .map_err(|_| ParseVersionError::InvalidNumber(component.to_string()))
+            }
+        });
+        let major = components.next().transpose()?.flatten();
+        let minor = components.next().transpose()?.flatten();
+        let patch = components.next().transpose()?.flatten();
+        if components.next().is_some() || (major.is_none() && !matches!(op, Op::Caret)) {
+            return Err(ParseVersionError::InvalidRequirement(s.to_string()));
+        }
+        Ok(Self {
+            op,
+            major,
+            minor: major.and(minor),
+            patch: major.and(minor).and(patch),
+            pre,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test_version_req {
+    use super::*;
+
+    fn matches(requirement: &str, version: &str) -> bool {
+        VersionReq::from_str(requirement)
+            .unwrap()
+            .matches(&Version::from_str(version).unwrap())
+    }
+
+    #[test]
+    fn caret() {
+        assert!(matches("^1.2.3", "1.9.0"));
+        assert!(!matches("^1.2.3", "2.0.0"));
+        assert!(!matches("^1.2.3", "1.2.2"));
+        assert!(matches("1.2.3", "1.2.4"));
+        assert!(matches("^0.2.3", "0.2.9"));
+        assert!(!matches("^0.2.3", "0.3.0"));
+        assert!(!matches("^0.0.3", "0.0.4"));
+        assert!(matches("^0", "0.9.0"));
+    }
+
+    #[test]
+    fn maximum_components() {
+        let max = u64::MAX;
+        assert!(matches(&format!("^{max}"), &format!("{max}.{max}.{max}")));
+        assert!(matches(&format!("~1.{max}"), &format!("1.{max}.5")));
+        assert!(matches(&format!("=1.2.{max}"), &format!("1.2.{max}")));
+        assert!(!matches(&format!(">{max}"), &format!("{max}.0.0")));
+        assert!(matches(&format!("<={max}"), &format!("{max}.1.0")));
+    }
+
+    #[test]
+    fn tilde() {
+        assert!(matches("~1.2.3", "1.2.9"));
+        assert!(!matches("~1.2.3", "1.3.0"));
+        assert!(matches("~1", "1.9.0"));
+    }
+
+    #[test]
+    fn comparisons() {
+        assert!(matches(">=1.0, <2.0", "1.5.0"));
+        assert!(!matches(">=1.0, <2.0", "2.0.0"));
+        assert!(matches(">= 1.0 < 2.0", "1.5.0"));
+        assert!(matches(">1.2", "1.3.0"));
+        assert!(!matches(">1.2", "1.2.9"));
+        assert!(matches("<=1.2", "1.2.9"));
+        assert!(matches("=1.2.3", "1.2.3"));
+        assert!(!matches("=1.2.3", "1.2.4"));
+    }
+
+    #[test]
+    fn hyphen_ranges() {
+        assert!(matches("1.2.3 - 2.0.0", "1.2.3"));
+        assert!(matches("1.2.3 - 2.0.0", "2.0.0"));
+        assert!(!matches("1.2.3 - 2.0.0", "2.0.1"));
+        assert!(!matches("1.2.3 - 2.0.0", "1.2.2"));
+        assert!(matches("1.2 - 2.3", "2.3.9"));
+        assert!(!matches("1.2 - 2.3", "2.4.0"));
+        assert!(matches("1.0.0 - 1.5.0 || ^3", "3.1.0"));
+        assert!(VersionReq::from_str("1.2.3 -").is_err());
+    }
+
+    #[test]
+    fn prereleases() {
+        assert!(!matches("^1.2.3", "2.0.0-rc.0"));
+        assert!(!matches("^1.2.3", "1.5.0-rc.0"));
+        assert!(!matches("<2.0.0", "2.0.0-rc.0"));
+        assert!(!matches("*", "1.0.0-rc.0"));
+        assert!(matches(">=1.0.0-alpha", "1.0.0-beta"));
+        assert!(matches(">=1.0.0-alpha", "1.0.1"));
+        assert!(!matches(">=1.0.0-alpha", "1.0.1-beta"));
+        assert!(matches("^2.0.0-rc.0", "2.0.0-rc.1"));
+        assert!(!matches("^2.0.0-rc.1", "2.0.0-rc.0"));
+        assert!(matches("^1.0.0 || ^2.0.0-rc.0", "2.0.0-rc.3"));
+    }
+
+    #[test]
+    fn wildcards_and_alternatives() {
+        assert!(matches("*", "5.0.0"));
+        assert!(matches("1.x", "1.9.0"));
+        assert!(!matches("=1.x", "2.0.0"));
+        assert!(matches("^1.0.0 || ^2.0.0", "2.1.0"));
+        assert!(!matches("^1.0.0 || ^2.0.0", "3.0.0"));
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(VersionReq::from_str(">=").is_err());
+        assert!(VersionReq::from_str("^1.a").is_err());
+        assert!(VersionReq::from_str(">*").is_err());
+    }
+}
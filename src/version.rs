@@ -0,0 +1,602 @@
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    error::Error,
+    fmt::{Display, Formatter},
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+
+use crate::ChangeType;
+
+/// A [Semantic Version](https://semver.org/) of a package, like `1.4.2` or `2.0.0-rc.3`.
+///
+/// Build metadata (like the `build.5` in `1.0.0+build.5`) is kept, but ignored when comparing
+/// versions, as semver requires.
+#[derive(Clone, Debug)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    /// The pre-release component, if this is a pre-release version.
+    pub pre: Option<Prerelease>,
+    /// The build metadata, without the leading `+`.
+    pub build: Option<String>,
+}
+
+impl Version {
+    /// Create a stable version (one without a [`Prerelease`] component).
+    #[must_use]
+    pub const fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+            pre: None,
+            build: None,
+        }
+    }
+
+    /// This version without its [`Prerelease`] component (or build metadata).
+    #[must_use]
+    pub const fn stable(&self) -> Self {
+        Self::new(self.major, self.minor, self.patch)
+    }
+
+    #[must_use]
+    pub const fn is_prerelease(&self) -> bool {
+        self.pre.is_some()
+    }
+
+    /// Apply a [`Bump`] to this version, returning the new (always stable) version.
+    ///
+    /// If [`BumpRules::initial_development`] is set and the major version is `0`, a
+    /// [`Bump::Major`] increments the minor version and all other bumps increment the patch
+    /// version.
+    ///
+    /// Bumping a pre-release version only increments a component if the pre-release doesn't
+    /// already account for it, so `2.0.0-rc.1` bumped by [`Bump::Major`] is `2.0.0`.
+    ///
+    /// Returns `None` if the component to increment is already [`u64::MAX`].
+    #[must_use]
+    pub fn bump(&self, bump: Bump, rules: &BumpRules) -> Option<Self> {
+        let bump = if rules.initial_development && self.major == 0 {
+            match bump {
+                Bump::Major => Bump::Minor,
+                Bump::Minor | Bump::Patch => Bump::Patch,
+            }
+        } else {
+            bump
+        };
+        let is_prerelease = self.is_prerelease();
+        match bump {
+            Bump::Major if is_prerelease && self.minor == 0 && self.patch == 0 => {
+                Some(self.stable())
+            }
+            Bump::Major => Some(Self::new(self.major.checked_add(1)?, 0, 0)),
+            Bump::Minor if is_prerelease && self.patch == 0 => Some(self.stable()),
+            Bump::Minor => Some(Self::new(self.major, self.minor.checked_add(1)?, 0)),
+            Bump::Patch if is_prerelease => Some(self.stable()),
+            Bump::Patch => Some(Self::new(
+                self.major,
+                self.minor,
+                self.patch.checked_add(1)?,
+            )),
+        }
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Version {}
+
+impl Hash for Version {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (self.major, self.minor, self.patch, &self.pre).hash(state);
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (&self.pre, &other.pre) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(first), Some(second)) => first.cmp(second),
+            })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(pre) = &self.pre {
+            write!(f, "-{pre}")?;
+        }
+        if let Some(build) = &self.build {
+            write!(f, "+{build}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Version {
+    type Err = ParseVersionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (rest, build) = match s.trim().split_once('+') {
+            Some((rest, build)) => (rest, Some(parse_build(build)?)),
+            None => (s.trim(), None),
+        };
+        let (stable, pre) = match rest.split_once('-') {
+            Some((stable, pre)) => (stable, Some(Prerelease::from_str(pre)?)),
+            None => (rest, None),
+        };
+        let mut components = stable.split('.').map(|component| {
+            component
+                .parse::<u64>()
+                .map_err(|_| ParseVersionError::InvalidNumber(component.to_string()))
+        });
+        let (Some(major), Some(minor), Some(patch), None) = (
+            components.next(),
+            components.next(),
+            components.next(),
+            components.next(),
+        ) else {
+            return Err(ParseVersionError::WrongNumberOfComponents);
+        };
+        Ok(Self {
+            pre,
+            build,
+            ..Self::new(major?, minor?, patch?)
+        })
+    }
+}
+
+/// Validate the build metadata of a version (after the `+`).
+fn parse_build(build: &str) -> Result<String, ParseVersionError> {
+    if build.split('.').all(is_identifier) {
+        Ok(build.to_string())
+    } else {
+        Err(ParseVersionError::InvalidBuild(build.to_string()))
+    }
+}
+
+/// Whether `identifier` is a valid (non-empty, ASCII alphanumeric or `-`) semver identifier.
+fn is_identifier(identifier: &str) -> bool {
+    !identifier.is_empty()
+        && identifier
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+#[cfg(feature = "serde")]
+serde_as_string!(Version);
+
+#[cfg(test)]
+mod test_version {
+    use super::*;
+
+    #[test]
+    fn it_parses_and_displays() {
+        let version = Version::from_str("1.4.2").unwrap();
+        assert_eq!(version, Version::new(1, 4, 2));
+        assert_eq!(version.to_string(), "1.4.2");
+    }
+
+    #[test]
+    fn it_rejects_invalid_versions() {
+        assert_eq!(
+            Version::from_str("1.4"),
+            Err(ParseVersionError::WrongNumberOfComponents)
+        );
+        assert_eq!(
+            Version::from_str("1.4.2.0"),
+            Err(ParseVersionError::WrongNumberOfComponents)
+        );
+        assert_eq!(
+            Version::from_str("1.x.2"),
+            Err(ParseVersionError::InvalidNumber("x".into()))
+        );
+    }
+
+    #[test]
+    fn it_bumps_stable_versions() {
+        let rules = BumpRules::default();
+        let version = Version::new(1, 4, 2);
+        assert_eq!(
+            version.bump(Bump::Patch, &rules),
+            Some(Version::new(1, 4, 3))
+        );
+        assert_eq!(
+            version.bump(Bump::Minor, &rules),
+            Some(Version::new(1, 5, 0))
+        );
+        assert_eq!(
+            version.bump(Bump::Major, &rules),
+            Some(Version::new(2, 0, 0))
+        );
+
+        let max = Version::new(u64::MAX, 1, u64::MAX);
+        assert_eq!(max.bump(Bump::Patch, &rules), None);
+        assert_eq!(
+            max.bump(Bump::Minor, &rules),
+            Some(Version::new(u64::MAX, 2, 0))
+        );
+        assert_eq!(max.bump(Bump::Major, &rules), None);
+    }
+
+    #[test]
+    fn it_bumps_initial_development_versions() {
+        let rules = BumpRules::default();
+        let version = Version::new(0, 4, 2);
+        assert_eq!(
+            version.bump(Bump::Patch, &rules),
+            Some(Version::new(0, 4, 3))
+        );
+        assert_eq!(
+            version.bump(Bump::Minor, &rules),
+            Some(Version::new(0, 4, 3))
+        );
+        assert_eq!(
+            version.bump(Bump::Major, &rules),
+            Some(Version::new(0, 5, 0))
+        );
+
+        let rules = BumpRules {
+            initial_development: false,
+            ..BumpRules::default()
+        };
+        assert_eq!(
+            version.bump(Bump::Minor, &rules),
+            Some(Version::new(0, 5, 0))
+        );
+        assert_eq!(
+            version.bump(Bump::Major, &rules),
+            Some(Version::new(1, 0, 0))
+        );
+    }
+
+    #[test]
+    fn it_parses_and_displays_prereleases() {
+        let version = Version::from_str("2.0.0-rc.3").unwrap();
+        assert_eq!(version.pre, Some(Prerelease::new("rc", 3)));
+        assert_eq!(version.to_string(), "2.0.0-rc.3");
+        for version in [
+            "1.0.0-alpha",
+            "0.0.0-development",
+            "1.0.0-0.3.7",
+            "1.0.0-x.7.z.92",
+            "1.0.0-x-y-z.--",
+        ] {
+            assert_eq!(Version::from_str(version).unwrap().to_string(), version);
+        }
+        for invalid in ["alpha..1", "01", "", "alpha_1"] {
+            assert_eq!(
+                Version::from_str(&format!("1.0.0-{invalid}")),
+                Err(ParseVersionError::InvalidPrerelease(invalid.into()))
+            );
+        }
+    }
+
+    #[test]
+    fn it_keeps_but_ignores_build_metadata() {
+        let version = Version::from_str("1.0.0-beta+exp.sha.5114f85").unwrap();
+        assert_eq!(version.build.as_deref(), Some("exp.sha.5114f85"));
+        assert_eq!(version.to_string(), "1.0.0-beta+exp.sha.5114f85");
+        assert_eq!(
+            Version::from_str("1.0.0+build.5").unwrap(),
+            Version::new(1, 0, 0)
+        );
+        assert_eq!(
+            Version::from_str("1.0.0+"),
+            Err(ParseVersionError::InvalidBuild(String::new()))
+        );
+    }
+
+    #[test]
+    fn it_orders_prereleases_before_stable() {
+        let mut versions = [
+            "2.0.0",
+            "2.0.0-rc.10",
+            "1.9.9",
+            "2.0.0-beta.1",
+            "2.0.0-rc.2",
+            "2.0.0-beta",
+            "2.0.0-1",
+        ]
+        .map(|version| Version::from_str(version).unwrap());
+        versions.sort();
+        assert_eq!(
+            versions.map(|version| version.to_string()),
+            [
+                "1.9.9",
+                "2.0.0-1",
+                "2.0.0-beta",
+                "2.0.0-beta.1",
+                "2.0.0-rc.2",
+                "2.0.0-rc.10",
+                "2.0.0"
+            ]
+        );
+    }
+
+    #[test]
+    fn it_bumps_prereleases() {
+        let rules = BumpRules::default();
+        let version = Version::from_str("2.0.0-rc.1").unwrap();
+        assert_eq!(
+            version.bump(Bump::Major, &rules),
+            Some(Version::new(2, 0, 0))
+        );
+        assert_eq!(
+            version.bump(Bump::Patch, &rules),
+            Some(Version::new(2, 0, 0))
+        );
+
+        let version = Version::from_str("1.4.1-rc.1").unwrap();
+        assert_eq!(
+            version.bump(Bump::Patch, &rules),
+            Some(Version::new(1, 4, 1))
+        );
+        assert_eq!(
+            version.bump(Bump::Minor, &rules),
+            Some(Version::new(1, 5, 0))
+        );
+        assert_eq!(
+            version.bump(Bump::Major, &rules),
+            Some(Version::new(2, 0, 0))
+        );
+    }
+}
+
+/// The pre-release component of a [`Version`], like the `rc.3` in `2.0.0-rc.3` or the `alpha`
+/// in `1.0.0-alpha`.
+///
+/// Pre-releases are ordered by [semver precedence](https://semver.org/#spec-item-11).
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Prerelease {
+    /// The `.`-separated identifiers, there's always at least one.
+    pub identifiers: Vec<PrereleaseIdentifier>,
+}
+
+impl Prerelease {
+    /// A pre-release like `rc.3`, made of a `label` (which may itself contain `.`) followed by a
+    /// `number`.
+    #[must_use]
+    pub fn new(label: &str, number: u64) -> Self {
+        let mut identifiers: Vec<PrereleaseIdentifier> = label
+            .split('.')
+            .filter(|identifier| !identifier.is_empty())
+            .map(PrereleaseIdentifier::from)
+            .collect();
+        identifiers.push(PrereleaseIdentifier::Numeric(number));
+        Self { identifiers }
+    }
+
+    /// The label and number of a pre-release which ends with a number, like `("rc", 3)` for
+    /// `rc.3`. `None` if the last identifier isn't a number, like in `alpha`.
+    #[must_use]
+    pub fn numbered(&self) -> Option<(String, u64)> {
+        let (PrereleaseIdentifier::Numeric(number), label) = self.identifiers.split_last()? else {
+            return None;
+        };
+        let label = label
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(".");
+        Some((label, *number))
+    }
+}
+
+impl Display for Prerelease {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (index, identifier) in self.identifiers.iter().enumerate() {
+            if index > 0 {
+                f.write_str(".")?;
+            }
+            write!(f, "{identifier}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Prerelease {
+    type Err = ParseVersionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseVersionError::InvalidPrerelease(s.to_string());
+        let identifiers = s
+            .split('.')
+            .map(|identifier| {
+                if !is_identifier(identifier) {
+                    return Err(invalid());
+                }
+                let identifier = PrereleaseIdentifier::from(identifier);
+                // Numeric identifiers can't have leading zeros (or be too large to compare)
+                match &identifier {
+                    PrereleaseIdentifier::Alphanumeric(text)
+                        if text.chars().all(|c| c.is_ascii_digit()) =>
+                    {
+                        Err(invalid())
+                    }
+                    _ => Ok(identifier),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { identifiers })
+    }
+}
+
+/// A single `.`-separated part of a [`Prerelease`].
+///
+/// Numeric identifiers are compared numerically and always have lower precedence than
+/// alphanumeric ones, which are compared in ASCII order.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum PrereleaseIdentifier {
+    Numeric(u64),
+    Alphanumeric(String),
+}
+
+impl From<&str> for PrereleaseIdentifier {
+    /// Digits without a leading zero (other than `0` itself) which fit in a [`u64`] are
+    /// [`PrereleaseIdentifier::Numeric`], everything else is
+    /// [`PrereleaseIdentifier::Alphanumeric`].
+    fn from(identifier: &str) -> Self {
+        match identifier.parse() {
+            Ok(number)
+                if identifier.chars().all(|c| c.is_ascii_digit())
+                    && (identifier == "0" || !identifier.starts_with('0')) =>
+            {
+                Self::Numeric(number)
+            }
+            _ => Self::Alphanumeric(identifier.to_string()),
+        }
+    }
+}
+
+impl Display for PrereleaseIdentifier {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Numeric(number) => write!(f, "{number}"),
+            Self::Alphanumeric(text) => f.write_str(text),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+serde_as_string!(Prerelease);
+
+/// The component of a [`Version`] which should be incremented.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Bump {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl Display for Bump {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Bump::Patch => f.write_str("patch"),
+            Bump::Minor => f.write_str("minor"),
+            Bump::Major => f.write_str("major"),
+        }
+    }
+}
+
+impl From<Bump> for ChangeType {
+    fn from(bump: Bump) -> Self {
+        match bump {
+            Bump::Patch => ChangeType::Patch,
+            Bump::Minor => ChangeType::Minor,
+            Bump::Major => ChangeType::Major,
+        }
+    }
+}
+
+/// Determines how each [`ChangeType`] affects a [`Version`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct BumpRules {
+    /// The [`Bump`] to use for each [`ChangeType::Custom`] label.
+    ///
+    /// Labels which are not in this map use [`BumpRules::default_custom`].
+    pub custom: HashMap<String, Bump>,
+    /// The [`Bump`] to use for any [`ChangeType::Custom`] not listed in [`BumpRules::custom`].
+    /// Defaults to [`Bump::Patch`].
+    pub default_custom: Bump,
+    /// Follow the semver rules for [initial development](https://semver.org/#spec-item-4):
+    /// while the major version is `0`, breaking changes bump the minor version and everything else
+    /// bumps the patch version. Defaults to `true`.
+    pub initial_development: bool,
+}
+
+impl Default for BumpRules {
+    fn default() -> Self {
+        Self {
+            custom: HashMap::new(),
+            default_custom: Bump::Patch,
+            initial_development: true,
+        }
+    }
+}
+
+impl BumpRules {
+    /// The [`Bump`] that a single change of this [`ChangeType`] requires, `None` for
+    /// [`ChangeType::None`].
+    #[must_use]
+    pub fn bump_for(&self, change_type: &ChangeType) -> Option<Bump> {
+        match change_type {
+            ChangeType::None => None,
+            ChangeType::Patch => Some(Bump::Patch),
+            ChangeType::Minor => Some(Bump::Minor),
+            ChangeType::Major => Some(Bump::Major),
+            ChangeType::Custom(label) => Some(
+                self.custom
+                    .get(label)
+                    .copied()
+                    .unwrap_or(self.default_custom),
+            ),
+        }
+    }
+}
+
+/// The error that occurs when a string is not a valid [`Version`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseVersionError {
+    /// The version did not have exactly three components (`major.minor.patch`).
+    WrongNumberOfComponents,
+    /// One of the components was not a valid number.
+    InvalidNumber(String),
+    /// The pre-release component was not made of valid identifiers, like `rc.1` or `alpha`.
+    InvalidPrerelease(String),
+    /// The build metadata was not made of valid identifiers, like `build.5`.
+    InvalidBuild(String),
+    /// A [`crate::VersionReq`] contained an operator without a version, or an operator that
+    /// can't be used with a wildcard.
+    InvalidRequirement(String),
+}
+
+impl Display for ParseVersionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::WrongNumberOfComponents => {
+                f.write_str("a version must be in the format `major.minor.patch`")
+            }
+            Self::InvalidNumber(component) => {
+                write!(f, "`{component}` is not a valid version number")
+            }
+            Self::InvalidPrerelease(pre) => {
+                write!(
+                    f,
+                    "`{pre}` is not a valid pre-release, expected identifiers like `rc.1`"
+                )
+            }
+            Self::InvalidBuild(build) => {
+                write!(f, "`{build}` is not valid build metadata")
+            }
+            Self::InvalidRequirement(requirement) => {
+                write!(f, "`{requirement}` is not a valid version requirement")
+            }
+        }
+    }
+}
+
+impl Error for ParseVersionError {}
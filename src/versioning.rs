@@ -1,16 +1,20 @@
 use std::{
     cmp::Ordering,
-    collections::HashMap,
     convert::Infallible,
     error::Error,
     fmt::{Display, Formatter},
+    str::FromStr,
 };
 
 /// Describes how a [`crate::Change`] affects the version of relevant packages.
 ///
 /// This is guaranteed to never be empty, as a changeset must always apply to at least one package.
+///
+/// Packages are kept in the order they were first added (for example, the order they're listed in
+/// a change file), so writing a [`crate::Change`] is reproducible. Adding a package which is
+/// already present replaces its [`ChangeType`] without moving it.
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct Versioning(HashMap<PackageName, ChangeType>);
+pub struct Versioning(Vec<(PackageName, ChangeType)>);
 
 impl From<(&str, ChangeType)> for Versioning {
     fn from(value: (&str, ChangeType)) -> Self {
@@ -21,9 +25,7 @@ impl From<(&str, ChangeType)> for Versioning {
 
 impl From<(PackageName, ChangeType)> for Versioning {
     fn from(value: (PackageName, ChangeType)) -> Self {
-        let mut map = HashMap::new();
-        map.insert(value.0, value.1);
-        Self(map)
+        Self(vec![value])
     }
 }
 
@@ -42,7 +44,7 @@ impl Versioning {
         ParseError: Into<BuildVersioningError>,
         Iter: IntoIterator<Item = (Key, Value)>,
     {
-        let map = iter
+        let versioning = iter
             .into_iter()
             .map(|(key, value)| {
                 value
@@ -50,16 +52,27 @@ impl Versioning {
                     .map_err(Into::into)
                     .map(|value| (key.into(), value))
             })
-            .collect::<Result<HashMap<PackageName, ChangeType>, BuildVersioningError>>()?;
-        if map.is_empty() {
+            .collect::<Result<Versioning, BuildVersioningError>>()?;
+        if versioning.is_empty() {
             Err(BuildVersioningError::EmptyVersioningError)
         } else {
-            Ok(Self(map))
+            Ok(versioning)
         }
     }
 
+    /// Every package and its [`ChangeType`], in the order they were added.
     pub fn iter(&self) -> impl Iterator<Item = (&PackageName, &ChangeType)> {
-        self.0.iter()
+        self.0
+            .iter()
+            .map(|(package_name, change_type)| (package_name, change_type))
+    }
+
+    /// The [`ChangeType`] for `package_name`, if this affects it.
+    #[must_use]
+    pub fn get(&self, package_name: &str) -> Option<&ChangeType> {
+        self.iter()
+            .find(|(existing, _)| *existing == package_name)
+            .map(|(_, change_type)| change_type)
     }
 
     #[must_use]
@@ -75,7 +88,7 @@ impl Versioning {
 
 impl IntoIterator for Versioning {
     type Item = (PackageName, ChangeType);
-    type IntoIter = std::collections::hash_map::IntoIter<PackageName, ChangeType>;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.0.into_iter()
@@ -84,7 +97,54 @@ impl IntoIterator for Versioning {
 
 impl FromIterator<(PackageName, ChangeType)> for Versioning {
     fn from_iter<T: IntoIterator<Item = (PackageName, ChangeType)>>(iter: T) -> Self {
-        Self(iter.into_iter().collect())
+        let mut versioning: Vec<(PackageName, ChangeType)> = Vec::new();
+        for (package_name, change_type) in iter {
+            if let Some((_, existing)) = versioning
+                .iter_mut()
+                .find(|(existing, _)| *existing == package_name)
+            {
+                *existing = change_type;
+            } else {
+                versioning.push((package_name, change_type));
+            }
+        }
+        Self(versioning)
+    }
+}
+
+/// Serializes as a map of package names to change types, in order.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Versioning {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Versioning {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = Versioning;
+
+            fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+                formatter.write_str("a non-empty map of package names to change types")
+            }
+
+            fn visit_map<A: serde::de::MapAccess<'de>>(
+                self,
+                mut map: A,
+            ) -> Result<Self::Value, A::Error> {
+                let mut entries: Vec<(PackageName, ChangeType)> = Vec::new();
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+                Versioning::try_from_iter(entries).map_err(serde::de::Error::custom)
+            }
+        }
+
+        deserializer.deserialize_map(Visitor)
     }
 }
 
@@ -120,6 +180,10 @@ pub type PackageName = String;
 /// is applied.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum ChangeType {
+    /// A change worth noting (like documentation or an internal refactor) which doesn't affect the
+    /// version. It's still included in [`crate::Release::changes`], but ignored by
+    /// [`crate::Release::change_type`].
+    None,
     Patch,
     Minor,
     Major,
@@ -130,6 +194,7 @@ impl Display for ChangeType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             ChangeType::Custom(label) => write!(f, "{label}"),
+            ChangeType::None => write!(f, "none"),
             ChangeType::Patch => write!(f, "patch"),
             ChangeType::Minor => write!(f, "minor"),
             ChangeType::Major => write!(f, "major"),
@@ -140,6 +205,7 @@ impl Display for ChangeType {
 impl From<&str> for ChangeType {
     fn from(s: &str) -> Self {
         match s {
+            "none" => ChangeType::None,
             "patch" => ChangeType::Patch,
             "minor" => ChangeType::Minor,
             "major" => ChangeType::Major,
@@ -151,6 +217,7 @@ impl From<&str> for ChangeType {
 impl From<String> for ChangeType {
     fn from(s: String) -> Self {
         match s.as_str() {
+            "none" => ChangeType::None,
             "patch" => ChangeType::Patch,
             "minor" => ChangeType::Minor,
             "major" => ChangeType::Major,
@@ -159,13 +226,27 @@ impl From<String> for ChangeType {
     }
 }
 
+impl FromStr for ChangeType {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from(s))
+    }
+}
+
+#[cfg(feature = "serde")]
+serde_as_string!(ChangeType);
+
 impl Ord for ChangeType {
     fn cmp(&self, other: &Self) -> Ordering {
         match (self, other) {
             (ChangeType::Custom(_), ChangeType::Custom(_))
+            | (ChangeType::None, ChangeType::None)
             | (ChangeType::Major, ChangeType::Major)
             | (ChangeType::Patch, ChangeType::Patch)
             | (ChangeType::Minor, ChangeType::Minor) => Ordering::Equal,
+            (ChangeType::None, _) => Ordering::Less,
+            (_, ChangeType::None) => Ordering::Greater,
             (ChangeType::Custom(_), _) => Ordering::Less,
             (_, ChangeType::Custom(_)) => Ordering::Greater,
             (ChangeType::Patch, _) => Ordering::Less,
@@ -181,3 +262,27 @@ impl PartialOrd for ChangeType {
         Some(self.cmp(other))
     }
 }
+
+#[cfg(test)]
+mod test_versioning {
+    use super::*;
+
+    #[test]
+    fn it_keeps_insertion_order() {
+        let versioning = Versioning::try_from_iter([
+            ("zebra", ChangeType::Patch),
+            ("apple", ChangeType::Minor),
+            ("zebra", ChangeType::Major),
+        ])
+        .unwrap();
+        assert_eq!(
+            versioning.iter().collect::<Vec<_>>(),
+            vec![
+                (&"zebra".to_string(), &ChangeType::Major),
+                (&"apple".to_string(), &ChangeType::Minor)
+            ]
+        );
+        assert_eq!(versioning.get("apple"), Some(&ChangeType::Minor));
+        assert_eq!(versioning.get("banana"), None);
+    }
+}
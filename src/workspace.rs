@@ -0,0 +1,684 @@
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fmt::{Display, Formatter},
+    path::PathBuf,
+};
+#[cfg(any(feature = "json", feature = "toml"))]
+use std::{path::Path, str::FromStr};
+
+#[cfg(feature = "toml")]
+use toml::{Table, Value};
+
+use crate::{ChangeSet, Dependency, PackageName, ParseVersionError, Planner, UniqueId, Version};
+#[cfg(any(feature = "json", feature = "toml"))]
+use crate::{VersionReq, glob};
+
+/// The packages in a repository, used to check that change files only name packages which exist
+/// (see [`Workspace::validate`]) and to plan releases (see [`Workspace::planner`]).
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Workspace {
+    pub packages: Vec<WorkspacePackage>,
+}
+
+/// A single package in a [`Workspace`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WorkspacePackage {
+    pub name: PackageName,
+    /// The directory containing the package's manifest.
+    pub path: PathBuf,
+    /// The current version of the package, if its manifest has one.
+    pub version: Option<Version>,
+    /// The other packages in the workspace which this package depends on. Development-only
+    /// dependencies aren't included, since they don't affect users of the package.
+    pub dependencies: Vec<Dependency>,
+}
+
+impl Workspace {
+    /// Discover the packages of a Cargo workspace from the `Cargo.toml` in `root`.
+    ///
+    /// This includes the root package (if there is one) and every directory matching the
+    /// `workspace.members` patterns (except those in `workspace.exclude`) which has a
+    /// `Cargo.toml`. Versions and dependencies inherited from the workspace (like
+    /// `version.workspace = true`) are resolved. Dependencies come from `dependencies` and
+    /// `build-dependencies`, including platform-specific ones like
+    /// `[target.'cfg(unix)'.dependencies]`.
+    ///
+    /// Requires the `toml` feature.
+    ///
+    /// # Errors
+    ///
+    /// - If a manifest can't be read or isn't valid TOML
+    /// - If a package doesn't have a name, or has an invalid version
+    /// - If a dependency on another package in the workspace has an invalid requirement
+    #[cfg(feature = "toml")]
+    pub fn from_cargo<P: AsRef<Path>>(root: P) -> Result<Self, WorkspaceError> {
+        let root = root.as_ref();
+        let root_manifest = root.join("Cargo.toml");
+        let manifest = read_toml(&root_manifest)?;
+        let workspace = manifest.get("workspace").and_then(Value::as_table);
+        let inherited = Inherited {
+            version: workspace
+                .and_then(|workspace| workspace.get("package"))
+                .and_then(|package| package.get("version")),
+            dependencies: workspace
+                .and_then(|workspace| workspace.get("dependencies"))
+                .and_then(Value::as_table),
+        };
+
+        let mut packages = Vec::new();
+        if manifest.contains_key("package") {
+            packages.push(cargo_package(root, &root_manifest, &manifest, &inherited)?);
+        }
+        let strings = |key: &str| -> Vec<&str> {
+            workspace
+                .and_then(|workspace| workspace.get(key))
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .collect()
+        };
+        let excluded: Vec<PathBuf> = strings("exclude")
+            .into_iter()
+            .map(|exclude| root.join(exclude))
+            .collect();
+        for pattern in strings("members") {
+            for dir in glob::expand_directories(root, pattern) {
+                let path = dir.join("Cargo.toml");
+                if dir == root
+                    || !path.is_file()
+                    || excluded.iter().any(|excluded| dir.starts_with(excluded))
+                    || packages
+                        .iter()
+                        .any(|(package, _): &(WorkspacePackage, _)| package.path == dir)
+                {
+                    continue;
+                }
+                let manifest = read_toml(&path)?;
+                packages.push(cargo_package(&dir, &path, &manifest, &inherited)?);
+            }
+        }
+        Self::link(packages)
+    }
+
+    /// Discover the packages of a JavaScript monorepo from the manifests in `root`.
+    ///
+    /// If there's a `pnpm-workspace.yaml`, its `packages` patterns are used. Otherwise, the
+    /// `workspaces` patterns of `package.json` are (either a list or `{"packages": [...]}`).
+    /// Patterns starting with `!` exclude directories. Without any patterns, the root
+    /// `package.json` is the only package.
+    ///
+    /// Dependencies come from `dependencies`, `peerDependencies`, and `optionalDependencies`. The
+    /// `workspace:` protocol is supported, and a plain version (like `1.2.3`) only matches that
+    /// exact version, like in npm.
+    ///
+    /// Requires the `json` feature.
+    ///
+    /// # Errors
+    ///
+    /// - If a manifest can't be read or isn't valid JSON (or YAML, for `pnpm-workspace.yaml`)
+    /// - If a package doesn't have a name, or has an invalid version
+    /// - If a dependency on another package in the workspace has an invalid requirement
+    #[cfg(feature = "json")]
+    pub fn from_javascript<P: AsRef<Path>>(root: P) -> Result<Self, WorkspaceError> {
+        let root = root.as_ref();
+        let root_manifest = root.join("package.json");
+        let pnpm_workspace = root.join("pnpm-workspace.yaml");
+        let patterns = if pnpm_workspace.is_file() {
+            let contents =
+                std::fs::read_to_string(&pnpm_workspace).map_err(|source| WorkspaceError::Io {
+                    path: pnpm_workspace.clone(),
+                    source,
+                })?;
+            pnpm_packages(&contents).map_err(|message| WorkspaceError::InvalidManifest {
+                path: pnpm_workspace,
+                message,
+            })?
+        } else {
+            let manifest = read_json(&root_manifest)?;
+            let workspaces = manifest.get("workspaces");
+            workspaces
+                .and_then(|workspaces| workspaces.get("packages"))
+                .or(workspaces)
+                .and_then(serde_json::Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(serde_json::Value::as_str)
+                .map(ToString::to_string)
+                .collect()
+        };
+        if patterns.is_empty() {
+            let manifest = read_json(&root_manifest)?;
+            let package = javascript_package(root, &root_manifest, &manifest)?;
+            return Self::link(vec![package]);
+        }
+
+        let (excluded, included): (Vec<&String>, Vec<&String>) = patterns
+            .iter()
+            .partition(|pattern| pattern.starts_with('!'));
+        let excluded: Vec<PathBuf> = excluded
+            .into_iter()
+            .flat_map(|pattern| glob::expand_directories(root, pattern.trim_start_matches('!')))
+            .collect();
+        let mut directories: Vec<PathBuf> = included
+            .into_iter()
+            .flat_map(|pattern| glob::expand_directories(root, pattern))
+            .filter(|dir| !excluded.contains(dir) && dir.join("package.json").is_file())
+            .collect();
+        directories.sort();
+        directories.dedup();
+        let packages = directories
+            .into_iter()
+            .map(|dir| {
+                let path = dir.join("package.json");
+                let manifest = read_json(&path)?;
+                javascript_package(&dir, &path, &manifest)
+            })
+            .collect::<Result<_, _>>()?;
+        Self::link(packages)
+    }
+
+    /// Keep only the dependencies of each package which are also in the workspace.
+    #[cfg(any(feature = "json", feature = "toml"))]
+    fn link(packages: Vec<(WorkspacePackage, Vec<RawDependency>)>) -> Result<Self, WorkspaceError> {
+        let versions: BTreeMap<PackageName, Option<Version>> = packages
+            .iter()
+            .map(|(package, _)| (package.name.clone(), package.version.clone()))
+            .collect();
+        let packages = packages
+            .into_iter()
+            .map(|(mut package, dependencies)| {
+                package.dependencies = dependencies
+                    .into_iter()
+                    .filter_map(|dependency| {
+                        let version = versions.get(&dependency.name)?;
+                        let requirement = if dependency.npm {
+                            npm_requirement(&dependency.requirement, version.as_ref())
+                        } else {
+                            dependency.requirement.clone()
+                        };
+                        Some(
+                            VersionReq::from_str(&requirement)
+                                .map(|requirement| Dependency {
+                                    name: dependency.name.clone(),
+                                    requirement,
+                                })
+                                .map_err(|err| WorkspaceError::InvalidManifest {
+                                    path: dependency.manifest,
+                                    message: format!(
+                                        "invalid requirement `{}` for `{}`: {err}",
+                                        dependency.requirement, dependency.name
+                                    ),
+                                }),
+                        )
+                    })
+                    .collect::<Result<_, _>>()?;
+                Ok(package)
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { packages })
+    }
+
+    /// The package with this name, if it's in the workspace.
+    #[must_use]
+    pub fn package(&self, name: &str) -> Option<&WorkspacePackage> {
+        self.packages.iter().find(|package| package.name == name)
+    }
+
+    /// The directory of every package, for [`crate::GitRepository::uncovered_packages`].
+    #[must_use]
+    pub fn package_paths(&self) -> BTreeMap<PackageName, PathBuf> {
+        self.packages
+            .iter()
+            .map(|package| (package.name.clone(), package.path.clone()))
+            .collect()
+    }
+
+    /// A [`Planner`] with the [`Planner::versions`] and [`Planner::dependencies`] of every
+    /// package, to combine with other settings like `NpmConfig::planner` (with the `json` feature).
+    #[must_use]
+    pub fn planner(&self) -> Planner {
+        Planner {
+            dependencies: self
+                .packages
+                .iter()
+                .filter(|package| !package.dependencies.is_empty())
+                .map(|package| (package.name.clone(), package.dependencies.clone()))
+                .collect(),
+            versions: self
+                .packages
+                .iter()
+                .filter_map(|package| Some((package.name.clone(), package.version.clone()?)))
+                .collect(),
+            ..Planner::default()
+        }
+    }
+
+    /// Find every package named in `changeset` which isn't in this workspace, suggesting the
+    /// closest match for each (to catch typos like `changeset: minor` for a `changesets` package).
+    #[must_use]
+    pub fn validate(&self, changeset: &ChangeSet) -> Vec<UnknownPackage> {
+        changeset
+            .releases()
+            .iter()
+            .filter(|release| self.package(&release.package_name).is_none())
+            .map(|release| UnknownPackage {
+                package_name: release.package_name.clone(),
+                changes: release
+                    .changes
+                    .iter()
+                    .map(|change| change.unique_id.as_ref().clone())
+                    .collect(),
+                suggestion: self.suggestion(&release.package_name).cloned(),
+            })
+            .collect()
+    }
+
+    /// The name of the package most similar to `name`, if any are similar enough to be a typo.
+    fn suggestion(&self, name: &str) -> Option<&PackageName> {
+        let max_distance = (name.chars().count() / 3).max(1);
+        self.packages
+            .iter()
+            .map(|package| (edit_distance(name, &package.name), &package.name))
+            .filter(|(distance, _)| *distance <= max_distance)
+            .min()
+            .map(|(_, name)| name)
+    }
+}
+
+#[cfg(feature = "toml")]
+fn read_toml(path: &Path) -> Result<Table, WorkspaceError> {
+    let contents = std::fs::read_to_string(path).map_err(|source| WorkspaceError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    Table::from_str(&contents).map_err(|err| WorkspaceError::InvalidManifest {
+        path: path.to_path_buf(),
+        message: err.to_string(),
+    })
+}
+
+/// A dependency as written in a manifest, before it's known whether it's in the workspace.
+#[cfg(any(feature = "json", feature = "toml"))]
+struct RawDependency {
+    name: PackageName,
+    requirement: String,
+    /// The manifest which declares the dependency.
+    manifest: PathBuf,
+    /// Whether `requirement` uses npm's syntax (see [`npm_requirement`]) instead of Cargo's.
+    npm: bool,
+}
+
+/// Values which a Cargo package can inherit from its workspace.
+#[cfg(feature = "toml")]
+struct Inherited<'a> {
+    version: Option<&'a Value>,
+    dependencies: Option<&'a Table>,
+}
+
+/// Read the package in a `Cargo.toml`.
+#[cfg(feature = "toml")]
+fn cargo_package(
+    dir: &Path,
+    path: &Path,
+    manifest: &Table,
+    inherited: &Inherited,
+) -> Result<(WorkspacePackage, Vec<RawDependency>), WorkspaceError> {
+    let invalid = |message: &str| WorkspaceError::InvalidManifest {
+        path: path.to_path_buf(),
+        message: message.to_string(),
+    };
+    let package = manifest
+        .get("package")
+        .ok_or_else(|| invalid("missing `package` table"))?;
+    let name = package
+        .get("name")
+        .and_then(Value::as_str)
+        .ok_or_else(|| invalid("missing `package.name`"))?;
+    let version = match package.get("version") {
+        Some(version) if is_inherited(version) => inherited.version.and_then(Value::as_str),
+        Some(version) => version.as_str(),
+        None => None,
+    };
+    // Platform-specific dependencies, like `[target.'cfg(unix)'.dependencies]`, count too
+    let targets = manifest
+        .get("target")
+        .and_then(Value::as_table)
+        .into_iter()
+        .flat_map(Table::values)
+        .filter_map(Value::as_table);
+    let dependencies = std::iter::once(manifest)
+        .chain(targets)
+        .flat_map(|table| {
+            ["dependencies", "build-dependencies"]
+                .into_iter()
+                .filter_map(|key| table.get(key).and_then(Value::as_table))
+        })
+        .flatten()
+        .map(|(key, dependency)| {
+            let dependency = inherited
+                .dependencies
+                .and_then(|dependencies| dependencies.get(key))
+                .filter(|_| is_inherited(dependency))
+                .unwrap_or(dependency);
+            let requirement = match dependency {
+                Value::String(requirement) => Some(requirement.as_str()),
+                dependency => dependency.get("version").and_then(Value::as_str),
+            };
+            RawDependency {
+                name: dependency
+                    .get("package")
+                    .and_then(Value::as_str)
+                    .unwrap_or(key)
+                    .to_string(),
+                requirement: requirement.unwrap_or("*").to_string(),
+                manifest: path.to_path_buf(),
+                npm: false,
+            }
+        })
+        .collect();
+    let package = WorkspacePackage {
+        name: name.to_string(),
+        path: dir.to_path_buf(),
+        version: parse_version(path, version)?,
+        dependencies: Vec::new(),
+    };
+    Ok((package, dependencies))
+}
+
+/// Whether a value in a `Cargo.toml` is inherited from the workspace, like
+/// `version.workspace = true`.
+#[cfg(feature = "toml")]
+fn is_inherited(value: &Value) -> bool {
+    value.get("workspace").and_then(Value::as_bool) == Some(true)
+}
+
+#[cfg(feature = "json")]
+fn read_json(path: &Path) -> Result<serde_json::Value, WorkspaceError> {
+    let contents = std::fs::read_to_string(path).map_err(|source| WorkspaceError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    serde_json::from_str(&contents).map_err(|err| WorkspaceError::InvalidManifest {
+        path: path.to_path_buf(),
+        message: err.to_string(),
+    })
+}
+
+/// Read the package in a `package.json`.
+#[cfg(feature = "json")]
+fn javascript_package(
+    dir: &Path,
+    path: &Path,
+    manifest: &serde_json::Value,
+) -> Result<(WorkspacePackage, Vec<RawDependency>), WorkspaceError> {
+    let name = manifest
+        .get("name")
+        .and_then(serde_json::Value::as_str)
+        .ok_or_else(|| WorkspaceError::InvalidManifest {
+            path: path.to_path_buf(),
+            message: "missing `name`".to_string(),
+        })?;
+    let version = manifest.get("version").and_then(serde_json::Value::as_str);
+    let dependencies = ["dependencies", "peerDependencies", "optionalDependencies"]
+        .into_iter()
+        .filter_map(|key| manifest.get(key).and_then(serde_json::Value::as_object))
+        .flatten()
+        .map(|(name, requirement)| RawDependency {
+            name: name.clone(),
+            requirement: requirement.as_str().unwrap_or("*").to_string(),
+            manifest: path.to_path_buf(),
+            npm: true,
+        })
+        .collect();
+    let package = WorkspacePackage {
+        name: name.to_string(),
+        path: dir.to_path_buf(),
+        version: parse_version(path, version)?,
+        dependencies: Vec::new(),
+    };
+    Ok((package, dependencies))
+}
+
+#[cfg(any(feature = "json", feature = "toml"))]
+fn parse_version(path: &Path, version: Option<&str>) -> Result<Option<Version>, WorkspaceError> {
+    version
+        .map(Version::from_str)
+        .transpose()
+        .map_err(|source| WorkspaceError::InvalidVersion {
+            path: path.to_path_buf(),
+            source,
+        })
+}
+
+/// Convert an npm dependency specifier to the syntax of [`VersionReq`].
+///
+/// `workspace:^` and `workspace:~` refer to the `version` of the dependency, and `workspace:*`
+/// accepts any version. Other `workspace:` specifiers are ranges. A plain version (like `1.2.3`)
+/// is exact. Specifiers which aren't ranges at all (like `file:../dependency` or
+/// `github-user/repo`) accept any version.
+#[cfg(any(feature = "json", feature = "toml"))]
+fn npm_requirement(specifier: &str, version: Option<&Version>) -> String {
+    let specifier = specifier.trim();
+    let specifier = specifier.strip_prefix("workspace:").unwrap_or(specifier);
+    match (specifier, version) {
+        ("^" | "~", Some(version)) => format!("{specifier}{version}"),
+        ("" | "*" | "^" | "~", _) => "*".to_string(),
+        _ if specifier.contains([':', '/']) => "*".to_string(),
+        _ if Version::from_str(specifier.trim_start_matches('v')).is_ok() => {
+            format!("={specifier}")
+        }
+        _ => specifier.to_string(),
+    }
+}
+
+/// The `packages` patterns of a `pnpm-workspace.yaml`.
+///
+/// Only the YAML needed for this list is supported: a top-level `packages:` key followed by
+/// `- pattern` items or a flow sequence (like `["a/*", "b/*"]`), where patterns may be quoted.
+#[cfg(feature = "json")]
+fn pnpm_packages(contents: &str) -> Result<Vec<String>, String> {
+    let mut patterns = Vec::new();
+    let mut in_packages = false;
+    for line in contents.lines() {
+        let line = strip_yaml_comment(line).trim_end();
+        if line.trim_start().is_empty() {
+            continue;
+        }
+        if line.starts_with([' ', '\t', '-']) {
+            if in_packages {
+                let item = line.trim_start().strip_prefix('-').ok_or_else(|| {
+                    format!(
+                        "expected a list item in `packages`, found `{}`",
+                        line.trim()
+                    )
+                })?;
+                patterns.push(unquote_yaml(item.trim()).to_string());
+            }
+            continue;
+        }
+        in_packages = false;
+        let Some(value) = line.strip_prefix("packages:").map(str::trim) else {
+            continue;
+        };
+        if value.is_empty() {
+            in_packages = true;
+        } else if let Some(items) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+            patterns.extend(
+                items
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .map(|item| unquote_yaml(item).to_string()),
+            );
+        } else {
+            return Err("`packages` must be a list".to_string());
+        }
+    }
+    Ok(patterns)
+}
+
+/// `line` without a trailing `# comment`, if it has one outside of quotes.
+#[cfg(feature = "json")]
+fn strip_yaml_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut previous = ' ';
+    for (index, c) in line.char_indices() {
+        if let Some(open) = quote {
+            if c == open {
+                quote = None;
+            }
+        } else if c == '\'' || c == '"' {
+            quote = Some(c);
+        } else if c == '#' && previous.is_whitespace() {
+            return line.get(..index).unwrap_or(line);
+        }
+        previous = c;
+    }
+    line
+}
+
+#[cfg(feature = "json")]
+fn unquote_yaml(value: &str) -> &str {
+    ['\'', '"']
+        .into_iter()
+        .find_map(|quote| value.strip_prefix(quote)?.strip_suffix(quote))
+        .unwrap_or(value)
+}
+
+/// The number of single-character insertions, deletions, or substitutions to turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = Vec::with_capacity(previous.len());
+        current.push(i + 1);
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous.get(j).map_or(usize::MAX, |distance| {
+                distance + usize::from(a_char != *b_char)
+            });
+            let deletion = previous
+                .get(j + 1)
+                .map_or(usize::MAX, |distance| distance + 1);
+            let insertion = current.get(j).map_or(usize::MAX, |distance| distance + 1);
+            current.push(substitution.min(deletion).min(insertion));
+        }
+        previous = current;
+    }
+    previous.last().copied().unwrap_or_default()
+}
+
+/// A package named in a change file which isn't in the [`Workspace`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UnknownPackage {
+    pub package_name: PackageName,
+    /// The changes which name this package.
+    pub changes: Vec<UniqueId>,
+    /// The name of a similar package in the workspace, which was probably intended.
+    pub suggestion: Option<PackageName>,
+}
+
+impl Display for UnknownPackage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown package `{}` in ", self.package_name)?;
+        for (index, unique_id) in self.changes.iter().enumerate() {
+            if index > 0 {
+                f.write_str(", ")?;
+            }
+            f.write_str(&unique_id.to_file_name())?;
+        }
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, ", did you mean `{suggestion}`?")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum WorkspaceError {
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// A manifest couldn't be parsed, or is missing required fields.
+    InvalidManifest { path: PathBuf, message: String },
+    InvalidVersion {
+        path: PathBuf,
+        source: ParseVersionError,
+    },
+}
+
+impl Display for WorkspaceError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WorkspaceError::Io { path, source } => {
+                write!(f, "could not read {}: {source}", path.display())
+            }
+            WorkspaceError::InvalidManifest { path, message } => {
+                write!(f, "invalid manifest {}: {message}", path.display())
+            }
+            WorkspaceError::InvalidVersion { path, source } => {
+                write!(f, "invalid version in {}: {source}", path.display())
+            }
+        }
+    }
+}
+
+impl Error for WorkspaceError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            WorkspaceError::Io { source, .. } => Some(source),
+            WorkspaceError::InvalidManifest { .. } => None,
+            WorkspaceError::InvalidVersion { source, .. } => Some(source),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_workspace {
+    use super::*;
+
+    #[test]
+    #[cfg(any(feature = "json", feature = "toml"))]
+    fn npm_requirements() {
+        let version = Version::from_str("1.2.3").ok();
+        let requirement = |specifier| npm_requirement(specifier, version.as_ref());
+        assert_eq!(requirement("workspace:*"), "*");
+        assert_eq!(requirement("workspace:^"), "^1.2.3");
+        assert_eq!(requirement("workspace:~"), "~1.2.3");
+        assert_eq!(requirement("workspace:^1.0.0"), "^1.0.0");
+        assert_eq!(requirement("1.2.3"), "=1.2.3");
+        assert_eq!(requirement(">=1.0.0 <2.0.0"), ">=1.0.0 <2.0.0");
+        assert_eq!(requirement("file:../dependency"), "*");
+        assert_eq!(requirement("user/repo#main"), "*");
+        assert_eq!(requirement("1.0.0 - 2.0.0"), "1.0.0 - 2.0.0");
+        assert_eq!(npm_requirement("workspace:^", None), "*");
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn pnpm_workspace() {
+        let contents = "# The packages\npackages:\n  - 'packages/*' # libraries\n  - \"apps/**\"\n  - '!**/test/**'\ncatalog:\n  react: ^18\n";
+        assert_eq!(
+            pnpm_packages(contents).unwrap(),
+            vec!["packages/*", "apps/**", "!**/test/**"]
+        );
+        assert_eq!(
+            pnpm_packages("packages: ['a/*', b]\n").unwrap(),
+            vec!["a/*", "b"]
+        );
+        assert!(pnpm_packages("packages: a/*\n").is_err());
+    }
+
+    #[test]
+    fn edit_distances() {
+        assert_eq!(edit_distance("changeset", "changesets"), 1);
+        assert_eq!(edit_distance("knpoe", "knope"), 2);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("same", "same"), 0);
+    }
+}
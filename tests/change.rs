@@ -30,15 +30,9 @@ fn create_change() {
     );
 
     let contents = std::fs::read_to_string(multiple_change_path).unwrap();
-    // Order of packages is not guaranteed, they are semantically the same in YAML
-    let first_possibility =
-        "---\nmy_package: minor\nmy_other_package: major\n---\n\n### This is a summary\n";
-    let second_possibility =
-        "---\nmy_other_package: major\nmy_package: minor\n---\n\n### This is a summary\n";
-    assert!(
-        contents == first_possibility || contents == second_possibility,
-        "Contents were not as expected: {}",
-        contents
+    assert_eq!(
+        contents,
+        "---\nmy_package: minor\nmy_other_package: major\n---\n\n### This is a summary\n"
     );
 }
 
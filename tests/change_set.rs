@@ -1,4 +1,7 @@
-use changesets::{ChangeSet, ChangeType, PackageChange, Release, UniqueId};
+use changesets::{
+    Bump, BumpRules, ChangeOrder, ChangeSet, ChangeType, PackageChange, Release, ReleaseOrder,
+    UniqueId, Version,
+};
 use tempfile::tempdir;
 
 #[test]
@@ -43,7 +46,8 @@ fn load_changeset() {
         vec![PackageChange {
             unique_id: UniqueId::exact(first_change_name).into(),
             change_type: first_change_type,
-            summary: first_change_summary.into()
+            summary: first_change_summary.into(),
+            provenance: None,
         },]
     );
     let second_release = releases
@@ -52,25 +56,147 @@ fn load_changeset() {
         .unwrap();
     assert_eq!(second_release.package_name, second_package);
     assert_eq!(second_release.change_type().unwrap(), &second_change_type);
-    // Order of reading files is probably not guaranteed
-    let first_variant = vec![
-        PackageChange {
-            unique_id: UniqueId::exact(first_change_name).into(),
-            change_type: second_package_type,
-            summary: first_change_summary.into(),
-        },
-        PackageChange {
-            unique_id: UniqueId::exact(second_change_name).into(),
-            change_type: second_change_type,
-            summary: second_change_summary.into(),
-        },
-    ];
-    let second_variant = first_variant.iter().cloned().rev().collect::<Vec<_>>();
-    assert!(
-        second_release.changes == first_variant || second_release.changes == second_variant,
-        "Expected {:?} or {:?}, got {:?}",
-        first_variant,
-        second_variant,
-        second_release.changes
+    // Changes are sorted by ID
+    assert_eq!(
+        second_release.changes,
+        vec![
+            PackageChange {
+                unique_id: UniqueId::exact(second_change_name).into(),
+                change_type: second_change_type,
+                summary: second_change_summary.into(),
+                provenance: None,
+            },
+            PackageChange {
+                unique_id: UniqueId::exact(first_change_name).into(),
+                change_type: second_package_type,
+                summary: first_change_summary.into(),
+                provenance: None,
+            },
+        ]
     );
 }
+
+#[test]
+fn next_version() {
+    let dir = tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("a_feature.md"),
+        "---\nmy_package: minor\n---\n\nA feature\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("a_custom_change.md"),
+        "---\nmy_package: security\n---\n\nA security fix\n",
+    )
+    .unwrap();
+
+    let releases: Vec<Release> = ChangeSet::from_directory(&dir).unwrap().into();
+    let release = releases.first().unwrap();
+    let rules = BumpRules::default();
+
+    assert_eq!(
+        release.next_version(&Version::new(1, 4, 2), &rules),
+        Some(Version::new(1, 5, 0))
+    );
+    assert_eq!(
+        release.next_version(&Version::new(0, 4, 2), &rules),
+        Some(Version::new(0, 4, 3))
+    );
+
+    let rules = BumpRules {
+        custom: [("security".to_string(), Bump::Major)].into(),
+        ..BumpRules::default()
+    };
+    assert_eq!(
+        release.next_version(&Version::new(1, 4, 2), &rules),
+        Some(Version::new(2, 0, 0))
+    );
+    assert_eq!(
+        release.next_version(&Version::new(0, 4, 2), &rules),
+        Some(Version::new(0, 5, 0))
+    );
+}
+
+#[test]
+fn load_changeset_leniently() {
+    let dir = tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("good.md"),
+        "---\nmy_package: minor\n---\n\nA feature\n",
+    )
+    .unwrap();
+    std::fs::write(dir.path().join("no_front_matter.md"), "A fix\n").unwrap();
+    std::fs::write(
+        dir.path().join("bad_line.md"),
+        "---\nmy_package patch\n---\n\nAnother fix\n",
+    )
+    .unwrap();
+
+    assert!(ChangeSet::from_directory(&dir).is_err());
+
+    let loaded = ChangeSet::from_directory_lenient(&dir).unwrap();
+    let releases = loaded.changeset.releases();
+    assert_eq!(releases.len(), 1);
+    assert_eq!(releases[0].changes.len(), 1);
+    let mut errors: Vec<String> = loaded.errors.iter().map(ToString::to_string).collect();
+    errors.sort();
+    assert_eq!(
+        errors,
+        vec![
+            format!(
+                "{}:2:17: invalid front matter, expected `package: change type`",
+                dir.path().join("bad_line.md").display()
+            ),
+            format!(
+                "{}:1:1: missing front matter, expected `---`",
+                dir.path().join("no_front_matter.md").display()
+            ),
+        ]
+    );
+    assert_eq!(loaded.into_result().unwrap_err().len(), 2);
+}
+
+#[test]
+fn sort_releases_and_changes() {
+    let dir = tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("a_fix.md"),
+        "---\nzebra: patch\napple: patch\n---\n\nA fix\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("b_feature.md"),
+        "---\nmango: minor\napple: minor\n---\n\nA feature\n",
+    )
+    .unwrap();
+
+    let mut changeset = ChangeSet::from_directory(&dir).unwrap();
+    let package_names = |changeset: &ChangeSet| {
+        changeset
+            .releases()
+            .iter()
+            .map(|release| release.package_name.clone())
+            .collect::<Vec<_>>()
+    };
+    // Files are read in order of their names
+    assert_eq!(package_names(&changeset), vec!["zebra", "apple", "mango"]);
+
+    changeset.sort_releases(&ReleaseOrder::PackageName);
+    assert_eq!(package_names(&changeset), vec!["apple", "mango", "zebra"]);
+
+    changeset.sort_releases(&ReleaseOrder::Custom(vec!["zebra".into()]));
+    assert_eq!(package_names(&changeset), vec!["zebra", "apple", "mango"]);
+
+    let apple_changes = |changeset: &ChangeSet| {
+        changeset.releases()[1]
+            .changes
+            .iter()
+            .map(|change| change.unique_id.to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(apple_changes(&changeset), vec!["a_fix", "b_feature"]);
+    changeset.sort_changes(ChangeOrder::ChangeType);
+    assert_eq!(apple_changes(&changeset), vec!["b_feature", "a_fix"]);
+    changeset.sort_changes(ChangeOrder::UniqueId);
+    assert_eq!(apple_changes(&changeset), vec!["a_fix", "b_feature"]);
+}
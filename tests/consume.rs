@@ -0,0 +1,226 @@
+use changesets::{ChangeSet, ConsumeAction, ConsumeMode, ConsumePlan, Planner, Release};
+use tempfile::tempdir;
+
+#[test]
+fn consume_releases() {
+    let dir = tempdir().unwrap();
+    let first = dir.path().join("first.md");
+    let second = dir.path().join("second.md");
+    let unrelated = dir.path().join("unrelated.md");
+    std::fs::write(&first, "---\nfirst_package: minor\n---\n\nA feature\n").unwrap();
+    std::fs::write(&second, "---\nfirst_package: patch\n---\n\nA fix\n").unwrap();
+    std::fs::write(&unrelated, "---\nsecond_package: patch\n---\n\nA fix\n").unwrap();
+
+    let changeset = ChangeSet::from_directory(&dir).unwrap();
+    let releases: Vec<Release> = changeset
+        .releases()
+        .iter()
+        .filter(|release| release.package_name == "first_package")
+        .cloned()
+        .collect();
+
+    let plan = changeset.consume(&releases, &ConsumeMode::Delete);
+    assert_eq!(
+        plan.to_string(),
+        format!("delete {}\ndelete {}\n", first.display(), second.display())
+    );
+    // Nothing happens until the plan is executed
+    assert!(first.exists());
+
+    plan.execute().unwrap();
+    assert!(!first.exists());
+    assert!(!second.exists());
+    assert!(unrelated.exists());
+}
+
+#[test]
+fn archive_releases() {
+    let dir = tempdir().unwrap();
+    let change = dir.path().join("a_change.md");
+    std::fs::write(&change, "---\nmy_package: minor\n---\n\nA feature\n").unwrap();
+    let archive = dir.path().join("archive");
+
+    let changeset = ChangeSet::from_directory(&dir).unwrap();
+    changeset
+        .consume(changeset.releases(), &ConsumeMode::Archive(archive.clone()))
+        .execute()
+        .unwrap();
+
+    assert!(!change.exists());
+    assert_eq!(
+        std::fs::read_to_string(archive.join("a_change.md")).unwrap(),
+        "---\nmy_package: minor\n---\n\nA feature\n"
+    );
+}
+
+#[test]
+fn archive_never_overwrites() {
+    let dir = tempdir().unwrap();
+    let change = dir.path().join("a_change.md");
+    std::fs::write(&change, "---\nmy_package: minor\n---\n\nA feature\n").unwrap();
+    let archive = dir.path().join("archive");
+    std::fs::create_dir(&archive).unwrap();
+    std::fs::write(archive.join("a_change.md"), "archived earlier").unwrap();
+
+    let changeset = ChangeSet::from_directory(&dir).unwrap();
+    let err = changeset
+        .consume(changeset.releases(), &ConsumeMode::Archive(archive.clone()))
+        .execute()
+        .unwrap_err();
+
+    assert_eq!(err.source.kind(), std::io::ErrorKind::AlreadyExists);
+    assert!(change.exists());
+    assert_eq!(
+        std::fs::read_to_string(archive.join("a_change.md")).unwrap(),
+        "archived earlier"
+    );
+}
+
+#[test]
+fn rollback_on_failure() {
+    let dir = tempdir().unwrap();
+    let change = dir.path().join("a_change.md");
+    let moved = dir.path().join("moved.md");
+    let other = dir.path().join("other.md");
+    std::fs::write(&change, "contents").unwrap();
+    std::fs::write(&other, "other contents").unwrap();
+
+    let plan = ConsumePlan {
+        actions: vec![
+            ConsumeAction::Delete(change.clone()),
+            ConsumeAction::Move {
+                from: other.clone(),
+                to: moved.clone(),
+            },
+            ConsumeAction::Delete(dir.path().join("missing.md")),
+        ],
+    };
+    let err = plan.execute().unwrap_err();
+
+    assert_eq!(
+        *err.action,
+        ConsumeAction::Delete(dir.path().join("missing.md"))
+    );
+    assert!(err.rollback_errors.is_empty());
+    assert_eq!(std::fs::read_to_string(&change).unwrap(), "contents");
+    assert_eq!(std::fs::read_to_string(&other).unwrap(), "other contents");
+    assert!(!moved.exists());
+}
+
+#[test]
+fn rollback_removes_created_archive_directory() {
+    let dir = tempdir().unwrap();
+    let change = dir.path().join("a_change.md");
+    std::fs::write(&change, "contents").unwrap();
+    let archive = dir.path().join("archive");
+    let nested = archive.join("nested");
+
+    let plan = ConsumePlan {
+        actions: vec![
+            ConsumeAction::Move {
+                from: change.clone(),
+                to: nested.join("a_change.md"),
+            },
+            ConsumeAction::Delete(dir.path().join("missing.md")),
+        ],
+    };
+    let err = plan.execute().unwrap_err();
+
+    assert!(err.rollback_errors.is_empty());
+    assert_eq!(std::fs::read_to_string(&change).unwrap(), "contents");
+    assert!(!archive.exists());
+}
+
+#[test]
+fn consume_part_of_a_change() {
+    let dir = tempdir().unwrap();
+    let shared = dir.path().join("shared.md");
+    let only_first = dir.path().join("only_first.md");
+    std::fs::write(
+        &shared,
+        "---\nfirst_package: minor\nsecond_package: major\n---\n\nA shared change\n",
+    )
+    .unwrap();
+    std::fs::write(&only_first, "---\nfirst_package: patch\n---\n\nA fix\n").unwrap();
+
+    let changeset = ChangeSet::from_directory(&dir).unwrap();
+    let planner = Planner {
+        packages: Some(["first_package".to_string()].into()),
+        ..Planner::default()
+    };
+    let releases = planner.plan(&changeset);
+    assert_eq!(releases.len(), 1);
+    assert_eq!(releases[0].package_name, "first_package");
+
+    let plan = changeset.consume(&releases, &ConsumeMode::Delete);
+    assert_eq!(
+        plan.to_string(),
+        format!(
+            "delete {}\nrewrite {} to only include second_package\n",
+            only_first.display(),
+            shared.display()
+        )
+    );
+    plan.execute().unwrap();
+
+    assert!(!only_first.exists());
+    assert_eq!(
+        std::fs::read_to_string(&shared).unwrap(),
+        "---\nsecond_package: major\n---\n\nA shared change\n"
+    );
+    let releases: Vec<Release> = ChangeSet::from_directory(&dir).unwrap().into();
+    assert_eq!(releases.len(), 1);
+    assert_eq!(releases[0].package_name, "second_package");
+}
+
+#[test]
+fn rewrite_keeps_formatting() {
+    let dir = tempdir().unwrap();
+    let shared = dir.path().join("shared.md");
+    std::fs::write(
+        &shared,
+        "---\n# Released separately\nsecond_package:  major\nfirst_package: minor\n---\n\n\nA shared change  \n",
+    )
+    .unwrap();
+
+    let changeset = ChangeSet::from_directory(&dir).unwrap();
+    let planner = Planner {
+        packages: Some(["first_package".to_string()].into()),
+        ..Planner::default()
+    };
+    changeset
+        .consume(&planner.plan(&changeset), &ConsumeMode::Delete)
+        .execute()
+        .unwrap();
+
+    assert_eq!(
+        std::fs::read_to_string(&shared).unwrap(),
+        "---\n# Released separately\nsecond_package:  major\n---\n\n\nA shared change  \n"
+    );
+}
+
+#[test]
+fn rewrite_keeps_crlf_line_endings() {
+    let dir = tempdir().unwrap();
+    let shared = dir.path().join("shared.md");
+    std::fs::write(
+        &shared,
+        "---\r\nsecond_package: major\r\nfirst_package: minor\r\n---\r\n\r\nA shared change\r\n\r\nWith details\r\n\r\n\r\n",
+    )
+    .unwrap();
+
+    let changeset = ChangeSet::from_directory(&dir).unwrap();
+    let planner = Planner {
+        packages: Some(["first_package".to_string()].into()),
+        ..Planner::default()
+    };
+    changeset
+        .consume(&planner.plan(&changeset), &ConsumeMode::Delete)
+        .execute()
+        .unwrap();
+
+    assert_eq!(
+        std::fs::read(&shared).unwrap(),
+        b"---\r\nsecond_package: major\r\n---\r\n\r\nA shared change\r\n\r\nWith details\r\n\r\n\r\n"
+    );
+}
@@ -0,0 +1,213 @@
+use std::{collections::BTreeMap, path::Path, process::Command};
+
+use changesets::{ChangeOrder, ChangeSet, GitError, GitRepository, UncoveredPackage};
+use tempfile::tempdir;
+
+fn write(root: &Path, path: &str, contents: &str) {
+    let path = root.join(path);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, contents).unwrap();
+}
+
+fn git(root: &Path, args: &[&str]) {
+    git_at(root, "2025-03-09T12:34:56+01:00", args);
+}
+
+fn git_at(root: &Path, date: &str, args: &[&str]) {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .env("GIT_AUTHOR_DATE", date)
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {args:?} failed");
+}
+
+fn commit_all(root: &Path, message: &str) {
+    git(root, &["add", "-A"]);
+    git(root, &["commit", "-q", "-m", message]);
+}
+
+#[test]
+fn uncovered_packages() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    git(root, &["init", "-q", "-b", "main"]);
+    write(root, "Cargo.toml", "");
+    write(root, "crates/core/src/lib.rs", "");
+    write(root, "crates/core/nested/src/lib.rs", "");
+    write(root, "crates/cli/src/main.rs", "");
+    write(root, ".changeset/old.md", "---\ncore: patch\n---\n\nOld\n");
+    commit_all(root, "Initial commit");
+
+    git(root, &["checkout", "-q", "-b", "feature"]);
+    write(root, "crates/core/src/lib.rs", "// changed");
+    write(root, "crates/core/nested/src/lib.rs", "// changed");
+    write(root, "crates/cli/src/main.rs", "// changed");
+    write(
+        root,
+        ".changeset/old.md",
+        "---\ncore: minor\ncli: patch\n---\n\nOld\n",
+    );
+    write(
+        root,
+        ".changeset/new.md",
+        "---\ncli: none\n---\n\nOnly a refactor\n",
+    );
+    commit_all(root, "Change things");
+    // Uncommitted changes count too
+    write(root, "README.md", "# Changed");
+
+    let packages = BTreeMap::from([
+        ("root".to_string(), root.to_path_buf()),
+        // `.` components are ignored
+        ("core".to_string(), "./crates/core".into()),
+        ("nested".to_string(), "crates/core/nested".into()),
+        ("cli".to_string(), "crates/cli".into()),
+    ]);
+    let repository = GitRepository::open(root.join("crates")).unwrap();
+    let uncovered = repository
+        .uncovered_packages("main", &packages, "./.changeset")
+        .unwrap();
+    assert_eq!(
+        uncovered,
+        vec![
+            UncoveredPackage {
+                package_name: "core".to_string(),
+                files: vec!["crates/core/src/lib.rs".into()],
+            },
+            UncoveredPackage {
+                package_name: "nested".to_string(),
+                files: vec!["crates/core/nested/src/lib.rs".into()],
+            },
+            UncoveredPackage {
+                package_name: "root".to_string(),
+                files: vec!["README.md".into()],
+            },
+        ]
+    );
+    assert_eq!(
+        uncovered[0].to_string(),
+        "`core` has changes without a change file (crates/core/src/lib.rs)"
+    );
+
+    let err = repository
+        .uncovered_packages("missing", &packages, ".changeset")
+        .unwrap_err();
+    assert!(matches!(err, GitError::Command { .. }));
+}
+
+#[test]
+fn provenance() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    git(root, &["init", "-q", "-b", "main"]);
+    write(
+        root,
+        ".changeset/feature.md",
+        "---\ncore: minor\n---\n\nA feature\n",
+    );
+    commit_all(root, "Add a feature\n\nWith details");
+    write(
+        root,
+        ".changeset/feature.md",
+        "---\ncore: minor\ncli: patch\n---\n\nA feature\n",
+    );
+    commit_all(root, "Also release cli");
+    write(
+        root,
+        ".changeset/uncommitted.md",
+        "---\ncore: patch\n---\n\nA fix\n",
+    );
+
+    let repository = GitRepository::open(root).unwrap();
+    let mut changeset = ChangeSet::from_directory(root.join(".changeset")).unwrap();
+    changeset.load_provenance(&repository).unwrap();
+    let core = &changeset.releases()[0];
+    assert_eq!(core.package_name, "core");
+    let provenance = core.changes[0].provenance.as_ref().unwrap();
+    assert_eq!(provenance.commit.len(), 40);
+    assert_eq!(provenance.author_name, "Test");
+    assert_eq!(provenance.author_email, "test@example.com");
+    assert_eq!(provenance.date, "2025-03-09T12:34:56+01:00");
+    assert_eq!(provenance.timestamp, 1_741_520_096);
+    assert_eq!(provenance.message, "Add a feature\n\nWith details");
+    assert_eq!(core.changes[1].provenance, None);
+    // Every package in a change file shares its provenance
+    let cli = &changeset.releases()[1];
+    assert_eq!(cli.changes[0].provenance.as_ref(), Some(provenance));
+}
+
+#[test]
+#[cfg(unix)]
+fn provenance_from_a_relative_directory() {
+    use std::path::{Component, PathBuf};
+
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    git(root, &["init", "-q", "-b", "main"]);
+    write(
+        root,
+        "packages/web/.changeset/feature.md",
+        "---\nweb: minor\n---\n\nA feature\n",
+    );
+    commit_all(root, "Add a feature");
+
+    // Relative to the current directory (where tests run), which isn't in the repository
+    let current_dir = std::env::current_dir().unwrap();
+    let up: PathBuf = current_dir
+        .components()
+        .skip(1)
+        .map(|_| Component::ParentDir)
+        .collect();
+    let changeset_directory = up
+        .join(root.strip_prefix("/").unwrap())
+        .join("packages/web/.changeset");
+    assert!(changeset_directory.is_relative());
+
+    let repository = GitRepository::open(root.join("packages/web")).unwrap();
+    let mut changeset = ChangeSet::from_directory(&changeset_directory).unwrap();
+    changeset.load_provenance(&repository).unwrap();
+    let provenance = changeset.releases()[0].changes[0].provenance.as_ref();
+    assert_eq!(provenance.unwrap().message, "Add a feature");
+}
+
+#[test]
+fn created_order_uses_commit_dates() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    git(root, &["init", "-q", "-b", "main"]);
+    write(root, ".changeset/b.md", "---\ncore: patch\n---\n\nFirst\n");
+    git(root, &["add", "-A"]);
+    git_at(
+        root,
+        "2025-03-10T00:00:00+05:00",
+        &["commit", "-q", "-m", "First"],
+    );
+    // Later, even though the date in a different time zone sorts earlier as text
+    write(root, ".changeset/a.md", "---\ncore: patch\n---\n\nSecond\n");
+    git(root, &["add", "-A"]);
+    git_at(
+        root,
+        "2025-03-09T20:00:00+00:00",
+        &["commit", "-q", "-m", "Second"],
+    );
+    write(
+        root,
+        ".changeset/0.md",
+        "---\ncore: patch\n---\n\nUncommitted\n",
+    );
+
+    let repository = GitRepository::open(root).unwrap();
+    let mut changeset = ChangeSet::from_directory(root.join(".changeset")).unwrap();
+    changeset.load_provenance(&repository).unwrap();
+    changeset.sort_changes(ChangeOrder::Created);
+    let summaries: Vec<&str> = changeset.releases()[0]
+        .changes
+        .iter()
+        .map(|change| change.summary.as_ref())
+        .collect();
+    assert_eq!(summaries, vec!["First", "Second", "Uncommitted"]);
+}
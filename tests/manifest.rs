@@ -0,0 +1,277 @@
+#![cfg(all(feature = "json", feature = "toml"))]
+
+use changesets::{
+    Bump, ManifestPlan, ManifestUpdate, PlannedRelease, ReleasePlan, Version, Workspace,
+    WorkspaceError,
+};
+use tempfile::tempdir;
+
+fn write(root: &std::path::Path, path: &str, contents: &str) {
+    let path = root.join(path);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, contents).unwrap();
+}
+
+fn release(name: &str, old: Version, new: Version) -> PlannedRelease {
+    PlannedRelease {
+        package_name: name.to_string(),
+        bump: Some(Bump::Minor),
+        old_version: Some(old),
+        new_version: Some(new),
+        changes: Vec::new(),
+    }
+}
+
+#[test]
+fn update_cargo_manifests() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    write(
+        root,
+        "Cargo.toml",
+        "[workspace]\nmembers = [\"crates/*\"]\n",
+    );
+    write(
+        root,
+        "crates/core/Cargo.toml",
+        "[package]\nname = \"core\"\nversion = \"1.0.0\" # Managed by changesets\n",
+    );
+    write(
+        root,
+        "crates/cli/Cargo.toml",
+        r#"[package]
+name = "cli"
+version = "0.1.0"
+
+[dependencies]
+# The core library
+core = { path = "../core", version = "^1.0.0" }
+serde = "1.0.0"
+
+[target.'cfg(unix)'.dependencies.core]
+path = "../core"
+version = "=1.0.0"
+"#,
+    );
+    write(
+        root,
+        "Cargo.lock",
+        r#"version = 4
+
+[[package]]
+name = "cli"
+version = "0.1.0"
+
+[[package]]
+name = "core"
+version = "1.0.0"
+
+[[package]]
+name = "serde"
+version = "1.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#,
+    );
+
+    let workspace = Workspace::from_cargo(root).unwrap();
+    assert_eq!(
+        workspace.manifests(),
+        vec![
+            root.join("Cargo.lock"),
+            root.join("crates/cli/Cargo.toml"),
+            root.join("crates/core/Cargo.toml"),
+        ]
+    );
+    let plan = ReleasePlan {
+        changes: Vec::new(),
+        releases: vec![release(
+            "core",
+            Version::new(1, 0, 0),
+            Version::new(1, 1, 0),
+        )],
+    };
+    let updates = plan.update_manifests(workspace.manifests()).unwrap();
+    assert_eq!(
+        updates.to_string(),
+        format!(
+            r#"--- {lock}
++++ {lock}
+@@ -9,1 +9,1 @@
+-version = "1.0.0"
++version = "1.1.0"
+--- {cli}
++++ {cli}
+@@ -7,1 +7,1 @@
+-core = {{ path = "../core", version = "^1.0.0" }}
++core = {{ path = "../core", version = "^1.1.0" }}
+@@ -12,1 +12,1 @@
+-version = "=1.0.0"
++version = "=1.1.0"
+--- {core}
++++ {core}
+@@ -3,1 +3,1 @@
+-version = "1.0.0" # Managed by changesets
++version = "1.1.0" # Managed by changesets
+"#,
+            lock = root.join("Cargo.lock").display(),
+            cli = root.join("crates/cli/Cargo.toml").display(),
+            core = root.join("crates/core/Cargo.toml").display(),
+        )
+    );
+
+    // Nothing is written until the plan is executed
+    let lock = std::fs::read_to_string(root.join("Cargo.lock")).unwrap();
+    assert!(lock.contains("version = \"1.0.0\"\n\n[[package]]\nname = \"serde\""));
+    updates.execute().unwrap();
+    for update in &updates.updates {
+        assert_eq!(
+            std::fs::read_to_string(&update.path).unwrap(),
+            update.updated
+        );
+    }
+}
+
+#[test]
+fn update_package_json_and_pyproject() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    let package_json = r#"{
+  "name": "@scope/app",
+  "version": "0.1.0",
+  "dependencies": {
+    "@scope/core":   "1.0.0",
+    "@scope/utils": "workspace:^",
+    "react": "^18.0.0"
+  },
+  "devDependencies": {"@scope/core": "workspace:~1.0.0"},
+  "scripts": {"version": "echo \"0.1.0\""}
+}
+"#;
+    write(root, "package.json", package_json);
+    write(
+        root,
+        "pyproject.toml",
+        "[project]\nname = \"My_App\"\nversion = '0.1.0'  # the version\n",
+    );
+
+    let plan = ReleasePlan {
+        changes: Vec::new(),
+        releases: vec![
+            release("@scope/app", Version::new(0, 1, 0), Version::new(0, 2, 0)),
+            release("@scope/core", Version::new(1, 0, 0), Version::new(2, 0, 0)),
+            release("@scope/utils", Version::new(1, 0, 0), Version::new(1, 0, 1)),
+            release("my-app", Version::new(0, 1, 0), Version::new(0, 2, 0)),
+        ],
+    };
+    let updates = plan
+        .update_manifests([root.join("package.json"), root.join("pyproject.toml")])
+        .unwrap();
+    let [package_json_update, pyproject_update] = updates.updates.as_slice() else {
+        panic!("expected two updates, got {updates:?}");
+    };
+    assert_eq!(
+        package_json_update.updated,
+        package_json
+            .replace("\"version\": \"0.1.0\"", "\"version\": \"0.2.0\"")
+            .replace("\"1.0.0\"", "\"2.0.0\"")
+            .replace("workspace:~1.0.0", "workspace:~2.0.0")
+    );
+    assert_eq!(
+        pyproject_update.updated,
+        "[project]\nname = \"My_App\"\nversion = \"0.2.0\"  # the version\n"
+    );
+}
+
+#[test]
+fn unsupported_manifest() {
+    let dir = tempdir().unwrap();
+    write(dir.path(), "setup.py", "");
+    let err = ReleasePlan {
+        changes: Vec::new(),
+        releases: Vec::new(),
+    }
+    .update_manifests([dir.path().join("setup.py")])
+    .unwrap_err();
+    assert!(matches!(err, WorkspaceError::InvalidManifest { .. }));
+}
+
+#[test]
+fn update_inherited_workspace_version() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    write(
+        root,
+        "Cargo.toml",
+        "[workspace]\nmembers = [\"crates/*\"]\n\n[workspace.package]\nversion = \"1.0.0\"\n",
+    );
+    for name in ["core", "cli"] {
+        write(
+            root,
+            &format!("crates/{name}/Cargo.toml"),
+            &format!("[package]\nname = \"{name}\"\nversion.workspace = true\n"),
+        );
+    }
+    let workspace = Workspace::from_cargo(root).unwrap();
+    // A virtual workspace root isn't a package, so it's not one of the manifests
+    assert!(!workspace.manifests().contains(&root.join("Cargo.toml")));
+
+    let both = ReleasePlan {
+        changes: Vec::new(),
+        releases: vec![
+            release("core", Version::new(1, 0, 0), Version::new(1, 1, 0)),
+            release("cli", Version::new(1, 0, 0), Version::new(1, 1, 0)),
+        ],
+    };
+    let updates = both.update_manifests(workspace.manifests()).unwrap();
+    let [update] = updates.updates.as_slice() else {
+        panic!("expected one update, got {updates:?}");
+    };
+    assert_eq!(update.path, root.join("Cargo.toml"));
+    assert!(
+        update
+            .updated
+            .ends_with("[workspace.package]\nversion = \"1.1.0\"\n")
+    );
+
+    let only_core = ReleasePlan {
+        changes: Vec::new(),
+        releases: vec![release(
+            "core",
+            Version::new(1, 0, 0),
+            Version::new(1, 1, 0),
+        )],
+    };
+    let err = only_core
+        .update_manifests(workspace.manifests())
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("cli (not released), core 1.1.0"),
+        "{err}"
+    );
+}
+
+#[test]
+fn restore_manifests_when_execute_fails() {
+    let dir = tempdir().unwrap();
+    let written = dir.path().join("Cargo.toml");
+    std::fs::write(&written, "original").unwrap();
+    let plan = ManifestPlan {
+        updates: vec![
+            ManifestUpdate {
+                path: written.clone(),
+                original: "original".to_string(),
+                updated: "updated".to_string(),
+            },
+            ManifestUpdate {
+                path: dir.path().join("missing/Cargo.toml"),
+                original: String::new(),
+                updated: "updated".to_string(),
+            },
+        ],
+    };
+    let err = plan.execute().unwrap_err();
+
+    assert_eq!(err.path, dir.path().join("missing/Cargo.toml"));
+    assert!(err.rollback_errors.is_empty());
+    assert_eq!(std::fs::read_to_string(&written).unwrap(), "original");
+}
@@ -0,0 +1,298 @@
+use std::str::FromStr;
+
+use changesets::{
+    BumpRules, ChangeSet, ChangeType, Dependency, Group, Planner, Propagation, Release, Version,
+    VersionReq,
+};
+use tempfile::{TempDir, tempdir};
+
+fn changeset() -> (TempDir, ChangeSet) {
+    let dir = tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("breaking.md"),
+        "---\ncore: major\n---\n\nA breaking change\n",
+    )
+    .unwrap();
+    let changeset = ChangeSet::from_directory(&dir).unwrap();
+    (dir, changeset)
+}
+
+fn planner(propagation: Propagation) -> Planner {
+    Planner {
+        dependencies: [
+            ("cli".to_string(), vec![dependency("core", "^1.0")]),
+            ("app".to_string(), vec![dependency("cli", "^0.3")]),
+        ]
+        .into(),
+        propagation,
+        versions: [
+            ("core".to_string(), Version::new(1, 2, 0)),
+            ("cli".to_string(), Version::new(0, 3, 1)),
+        ]
+        .into(),
+        ..Planner::default()
+    }
+}
+
+fn dependency(name: &str, requirement: &str) -> Dependency {
+    Dependency {
+        name: name.to_string(),
+        requirement: VersionReq::from_str(requirement).unwrap(),
+    }
+}
+
+fn change_type<'a>(releases: &'a [Release], package_name: &str) -> Option<&'a ChangeType> {
+    releases
+        .iter()
+        .find(|release| release.package_name == package_name)
+        .and_then(Release::change_type)
+}
+
+#[test]
+fn propagate_patch() {
+    let (_dir, changeset) = changeset();
+    let releases = planner(Propagation::Patch).plan(&changeset);
+
+    assert_eq!(releases.len(), 3);
+    assert_eq!(change_type(&releases, "cli"), Some(&ChangeType::Patch));
+    assert_eq!(change_type(&releases, "app"), Some(&ChangeType::Patch));
+    let cli = releases
+        .iter()
+        .find(|release| release.package_name == "cli")
+        .unwrap();
+    assert_eq!(
+        cli.changes[0].summary.as_ref(),
+        "Updated dependency `core` to 2.0.0"
+    );
+}
+
+#[test]
+fn propagate_matching_bump() {
+    let (_dir, changeset) = changeset();
+    let releases = planner(Propagation::MatchBump).plan(&changeset);
+
+    assert_eq!(change_type(&releases, "cli"), Some(&ChangeType::Major));
+    assert_eq!(change_type(&releases, "app"), Some(&ChangeType::Major));
+}
+
+#[test]
+fn propagate_when_range_broken() {
+    let (_dir, changeset) = changeset();
+    let releases = planner(Propagation::WhenRangeBroken).plan(&changeset);
+
+    // `core` 2.0.0 doesn't match `^1.0`, but `cli` 0.3.2 still matches `^0.3`
+    assert_eq!(change_type(&releases, "cli"), Some(&ChangeType::Patch));
+    assert_eq!(change_type(&releases, "app"), None);
+    assert_eq!(releases.len(), 2);
+}
+
+#[test]
+fn groups() {
+    let dir = tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("feature.md"),
+        "---\nfixed_a: minor\nlinked_a: minor\n---\n\nA feature\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("fix.md"),
+        "---\nfixed_b: patch\nlinked_b: patch\n---\n\nA fix\n",
+    )
+    .unwrap();
+    let changeset = ChangeSet::from_directory(&dir).unwrap();
+    let planner = Planner {
+        groups: vec![
+            Group::Fixed(vec!["fixed_a".into(), "fixed_b".into(), "fixed_c".into()]),
+            Group::Linked(vec![
+                "linked_a".into(),
+                "linked_b".into(),
+                "linked_c".into(),
+            ]),
+        ],
+        ..Planner::default()
+    };
+    let releases = planner.plan(&changeset);
+
+    for package_name in ["fixed_a", "fixed_b", "fixed_c", "linked_a", "linked_b"] {
+        assert_eq!(
+            change_type(&releases, package_name),
+            Some(&ChangeType::Minor),
+            "{package_name}"
+        );
+    }
+    assert_eq!(change_type(&releases, "linked_c"), None);
+
+    let fixed_c = releases
+        .iter()
+        .find(|release| release.package_name == "fixed_c")
+        .unwrap();
+    assert!(fixed_c.changes.is_empty());
+    assert_eq!(
+        fixed_c.next_version(&Version::new(1, 0, 0), &BumpRules::default()),
+        Some(Version::new(1, 1, 0))
+    );
+}
+
+#[test]
+fn ignored_packages() {
+    let (_dir, changeset) = changeset();
+    let planner = Planner {
+        ignored: ["app".to_string()].into(),
+        ..planner(Propagation::Patch)
+    };
+    let releases = planner.plan(&changeset);
+
+    assert_eq!(change_type(&releases, "cli"), Some(&ChangeType::Patch));
+    assert_eq!(change_type(&releases, "app"), None);
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn release_plan_as_status_json() {
+    let (_dir, changeset) = changeset();
+    let plan = planner(Propagation::Patch).release_plan(&changeset);
+
+    assert_eq!(
+        plan.to_string(),
+        r#"{
+  "changesets": [
+    {
+      "releases": [
+        {
+          "name": "core",
+          "type": "major"
+        }
+      ],
+      "summary": "A breaking change",
+      "id": "breaking"
+    }
+  ],
+  "releases": [
+    {
+      "name": "core",
+      "type": "major",
+      "oldVersion": "1.2.0",
+      "changesets": [
+        "breaking"
+      ],
+      "newVersion": "2.0.0"
+    },
+    {
+      "name": "cli",
+      "type": "patch",
+      "oldVersion": "0.3.1",
+      "changesets": [],
+      "newVersion": "0.3.2"
+    }
+  ]
+}"#
+    );
+    // `app` has no known version, so it can't be in the JSON
+    assert!(
+        plan.releases
+            .iter()
+            .any(|release| release.package_name == "app")
+    );
+    assert_eq!(plan.to_json().unwrap()["releases"][1]["name"], "cli");
+}
+
+#[test]
+fn none_changes() {
+    let dir = tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("docs.md"),
+        "---\ncore: none\ncli: none\n---\n\nBetter docs\n",
+    )
+    .unwrap();
+    std::fs::write(dir.path().join("fix.md"), "---\ncli: patch\n---\n\nA fix\n").unwrap();
+    let changeset = ChangeSet::from_directory(&dir).unwrap();
+    let planner = planner(Propagation::Patch);
+
+    let releases = planner.plan(&changeset);
+    let core = releases
+        .iter()
+        .find(|release| release.package_name == "core")
+        .unwrap();
+    assert_eq!(core.changes.len(), 1);
+    assert_eq!(core.change_type(), None);
+    assert_eq!(
+        core.next_version(&Version::new(1, 2, 0), &planner.rules),
+        Some(Version::new(1, 2, 0))
+    );
+    assert_eq!(change_type(&releases, "cli"), Some(&ChangeType::Patch));
+
+    let planner = Planner {
+        skip_none_only: true,
+        ..planner
+    };
+    let releases = planner.plan(&changeset);
+    assert_eq!(
+        releases
+            .iter()
+            .map(|release| release.package_name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["cli", "app"]
+    );
+}
+
+#[test]
+fn propagation_keeps_pending_changes_of_unselected_packages() {
+    let (dir, _) = changeset();
+    std::fs::write(
+        dir.path().join("cli_breaking.md"),
+        "---\ncli: major\n---\n\nA breaking change in cli\n",
+    )
+    .unwrap();
+    let changeset = ChangeSet::from_directory(&dir).unwrap();
+    let planner = Planner {
+        packages: Some(["core".to_string()].into()),
+        ..planner(Propagation::Patch)
+    };
+    let releases = planner.plan(&changeset);
+
+    // `cli` is released because of `core`, so its own breaking change must be included
+    assert_eq!(change_type(&releases, "cli"), Some(&ChangeType::Major));
+    let cli = releases
+        .iter()
+        .find(|release| release.package_name == "cli")
+        .unwrap();
+    let unique_ids: Vec<String> = cli
+        .changes
+        .iter()
+        .map(|change| change.unique_id.to_string())
+        .collect();
+    assert_eq!(unique_ids, vec!["cli_breaking", "dependency:core"]);
+}
+
+#[test]
+fn fixed_groups_keep_pending_changes_of_unselected_packages() {
+    let (dir, _) = changeset();
+    std::fs::write(
+        dir.path().join("cli_fix.md"),
+        "---\ncli: patch\n---\n\nA fix in cli\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("app_breaking.md"),
+        "---\napp: major\n---\n\nA breaking change in app\n",
+    )
+    .unwrap();
+    let changeset = ChangeSet::from_directory(&dir).unwrap();
+    let planner = Planner {
+        packages: Some(["cli".to_string()].into()),
+        groups: vec![Group::Fixed(vec!["cli".into(), "app".into()])],
+        ..Planner::default()
+    };
+    let releases = planner.plan(&changeset);
+
+    // `app` is released because it's fixed to `cli`, so its own breaking change is included and
+    // raises the whole group
+    assert_eq!(change_type(&releases, "app"), Some(&ChangeType::Major));
+    assert_eq!(change_type(&releases, "cli"), Some(&ChangeType::Major));
+    let app = releases
+        .iter()
+        .find(|release| release.package_name == "app")
+        .unwrap();
+    assert_eq!(app.changes.len(), 1);
+    assert_eq!(app.changes[0].unique_id.to_string(), "app_breaking");
+}
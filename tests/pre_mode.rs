@@ -0,0 +1,109 @@
+use std::str::FromStr;
+
+use changesets::{BumpRules, ChangeSet, PreMode, Release, Version};
+use tempfile::tempdir;
+
+#[test]
+fn prerelease_then_graduate() {
+    let dir = tempdir().unwrap();
+    let rules = BumpRules::default();
+    let mut pre_mode = PreMode::new("rc");
+
+    std::fs::write(
+        dir.path().join("a_fix.md"),
+        "---\nmy_package: patch\n---\n\nA fix\n",
+    )
+    .unwrap();
+    let release = load_release(&dir);
+    let current = Version::new(1, 4, 2);
+    let next = pre_mode.next_version(&release, &current, &rules).unwrap();
+    assert_eq!(next.to_string(), "1.4.3-rc.0");
+    assert_eq!(pre_mode.unreleased_changes(&release).count(), 1);
+    pre_mode.record(&release, &current);
+
+    // Nothing new, but another pre-release was requested
+    let release = load_release(&dir);
+    assert_eq!(pre_mode.unreleased_changes(&release).count(), 0);
+    let next = pre_mode.next_version(&release, &next, &rules).unwrap();
+    assert_eq!(next.to_string(), "1.4.3-rc.1");
+    pre_mode.record(&release, &next);
+
+    // A breaking change moves the target version
+    std::fs::write(
+        dir.path().join("a_breaking_change.md"),
+        "---\nmy_package: major\n---\n\nA breaking change\n",
+    )
+    .unwrap();
+    let release = load_release(&dir);
+    let unreleased: Vec<_> = pre_mode.unreleased_changes(&release).collect();
+    assert_eq!(unreleased.len(), 1);
+    assert_eq!(unreleased[0].unique_id.to_string(), "a_breaking_change");
+    let next = pre_mode.next_version(&release, &next, &rules).unwrap();
+    assert_eq!(next.to_string(), "2.0.0-rc.0");
+    pre_mode.record(&release, &next);
+    assert_eq!(
+        pre_mode.initial_versions.get("my_package"),
+        Some(&Version::new(1, 4, 2))
+    );
+
+    let next = pre_mode.next_version(&release, &next, &rules).unwrap();
+    assert_eq!(next, Version::from_str("2.0.0-rc.1").unwrap());
+    assert_eq!(
+        pre_mode.graduate(&release, &next, &rules),
+        Some(Version::new(2, 0, 0))
+    );
+}
+
+#[test]
+fn released_changes_are_tracked_per_package() {
+    let dir = tempdir().unwrap();
+    let rules = BumpRules::default();
+    let mut pre_mode = PreMode::new("rc");
+
+    std::fs::write(
+        dir.path().join("a_shared_fix.md"),
+        "---\nfirst: patch\nsecond: patch\n---\n\nA fix\n",
+    )
+    .unwrap();
+    let releases: Vec<Release> = ChangeSet::from_directory(&dir).unwrap().into();
+    let (first, second) = (&releases[0], &releases[1]);
+    assert_eq!(first.package_name, "first");
+    let current = Version::new(1, 0, 0);
+    pre_mode.record(first, &current);
+
+    assert_eq!(pre_mode.unreleased_changes(first).count(), 0);
+    assert_eq!(pre_mode.unreleased_changes(second).count(), 1);
+    assert_eq!(
+        pre_mode.next_version(second, &current, &rules),
+        Some(Version::from_str("1.0.1-rc.0").unwrap())
+    );
+    assert_eq!(
+        pre_mode.graduate(second, &current, &rules),
+        Some(Version::new(1, 0, 1))
+    );
+}
+
+#[test]
+fn nothing_to_bump() {
+    let dir = tempdir().unwrap();
+    let rules = BumpRules::default();
+    let pre_mode = PreMode::new("rc");
+
+    std::fs::write(
+        dir.path().join("a_chore.md"),
+        "---\nmy_package: none\n---\n\nA chore\n",
+    )
+    .unwrap();
+    let release = load_release(&dir);
+    let current = Version::new(1, 2, 3);
+    assert_eq!(
+        pre_mode.next_version(&release, &current, &rules),
+        Some(current.clone())
+    );
+    assert_eq!(pre_mode.graduate(&release, &current, &rules), Some(current));
+}
+
+fn load_release(dir: &tempfile::TempDir) -> Release {
+    let releases: Vec<Release> = ChangeSet::from_directory(dir).unwrap().into();
+    releases.into_iter().next().unwrap()
+}
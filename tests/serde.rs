@@ -0,0 +1,180 @@
+#![cfg(feature = "serde")]
+
+use std::str::FromStr;
+
+#[cfg(feature = "json")]
+use changesets::NpmConfig;
+use changesets::{
+    Bump, Change, ChangeSet, ChangeType, ChangedFile, Dependency, PlannedChange, PlannedRelease,
+    Planner, Release, ReleasePlan, UncoveredPackage, UniqueId, UnknownPackage, Version, VersionReq,
+    Versioning, Workspace, WorkspacePackage,
+};
+use serde_json::json;
+use tempfile::tempdir;
+
+#[test]
+fn change_as_json() {
+    let change = Change {
+        unique_id: UniqueId::exact("a_change"),
+        versioning: Versioning::try_from_iter([
+            ("second", ChangeType::Custom("security".into())),
+            ("first", ChangeType::Minor),
+        ])
+        .unwrap(),
+        summary: "A change".into(),
+    };
+    let value = serde_json::to_value(&change).unwrap();
+    assert_eq!(
+        value,
+        json!({
+            "unique_id": "a_change",
+            "versioning": {"second": "security", "first": "minor"},
+            "summary": "A change",
+        })
+    );
+    assert_eq!(
+        serde_json::to_string(&change.versioning).unwrap(),
+        r#"{"second":"security","first":"minor"}"#
+    );
+    let json = serde_json::to_string(&change).unwrap();
+    assert_eq!(serde_json::from_str::<Change>(&json).unwrap(), change);
+
+    let empty = json!({"unique_id": "a_change", "versioning": {}, "summary": ""});
+    assert!(serde_json::from_value::<Change>(empty).is_err());
+}
+
+#[test]
+fn changeset_round_trip() {
+    let dir = tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("a_change.md"),
+        "---\nfirst: major\nsecond: patch\n---\n\nA change\n",
+    )
+    .unwrap();
+    let changeset = ChangeSet::from_directory(&dir).unwrap();
+
+    let json = serde_json::to_string(&changeset).unwrap();
+    let deserialized: ChangeSet = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, changeset);
+
+    let release: &Release = &changeset.releases()[0];
+    assert_eq!(
+        serde_json::to_value(release).unwrap(),
+        json!({
+            "package_name": "first",
+            "changes": [{"unique_id": "a_change", "change_type": "major", "summary": "A change"}],
+            "minimum_change_type": null,
+        })
+    );
+}
+
+#[test]
+fn options_and_config() {
+    assert_eq!(
+        serde_json::to_value(Version::from_str("1.2.3-rc.1").unwrap()).unwrap(),
+        json!("1.2.3-rc.1")
+    );
+    let planner: Planner = serde_json::from_value(json!({
+        "versions": {"first": "1.0.0"},
+        "propagation": "when_range_broken",
+    }))
+    .unwrap();
+    assert_eq!(planner.versions["first"], Version::new(1, 0, 0));
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn npm_config() {
+    let config = NpmConfig::from_str(r#"{"access": "public", "commit": false}"#).unwrap();
+    let value = serde_json::to_value(&config).unwrap();
+    assert_eq!(value["access"], "public");
+    assert_eq!(value["commit"], false);
+    assert_eq!(serde_json::from_value::<NpmConfig>(value).unwrap(), config);
+}
+
+#[test]
+fn release_plan_from_status_json() {
+    let plan = ReleasePlan {
+        changes: vec![PlannedChange {
+            unique_id: UniqueId::exact("a_change"),
+            summary: "A change".to_string(),
+            packages: vec![("core".to_string(), Some(Bump::Minor))],
+        }],
+        releases: vec![
+            PlannedRelease {
+                package_name: "core".to_string(),
+                bump: Some(Bump::Minor),
+                old_version: Some(Version::new(1, 0, 0)),
+                new_version: Some(Version::new(1, 1, 0)),
+                changes: vec![UniqueId::exact("a_change")],
+            },
+            PlannedRelease {
+                package_name: "docs".to_string(),
+                bump: None,
+                old_version: Some(Version::new(0, 1, 0)),
+                new_version: Some(Version::new(0, 1, 0)),
+                changes: Vec::new(),
+            },
+        ],
+    };
+    let value = serde_json::to_value(&plan).unwrap();
+    assert_eq!(value["releases"][1]["type"], "none");
+    assert_eq!(serde_json::from_value::<ReleasePlan>(value).unwrap(), plan);
+
+    let mut unversioned = plan.clone();
+    unversioned.releases[1].old_version = None;
+    let value = serde_json::to_value(&unversioned).unwrap();
+    assert_eq!(value["releases"].as_array().unwrap().len(), 1);
+
+    let release = &plan.releases[0];
+    let value = serde_json::to_value(release).unwrap();
+    assert_eq!(
+        serde_json::from_value::<PlannedRelease>(value).unwrap(),
+        *release
+    );
+}
+
+#[test]
+fn workspace_and_git_round_trip() {
+    let workspace = Workspace {
+        packages: vec![WorkspacePackage {
+            name: "cli".to_string(),
+            path: "crates/cli".into(),
+            version: Some(Version::new(0, 1, 0)),
+            dependencies: vec![Dependency {
+                name: "core".to_string(),
+                requirement: VersionReq::from_str("^1.0").unwrap(),
+            }],
+        }],
+    };
+    let json = serde_json::to_string(&workspace).unwrap();
+    assert_eq!(serde_json::from_str::<Workspace>(&json).unwrap(), workspace);
+
+    let unknown = UnknownPackage {
+        package_name: "clj".to_string(),
+        changes: vec![UniqueId::exact("a_change")],
+        suggestion: Some("cli".to_string()),
+    };
+    let json = serde_json::to_string(&unknown).unwrap();
+    assert_eq!(
+        serde_json::from_str::<UnknownPackage>(&json).unwrap(),
+        unknown
+    );
+
+    let changed = ChangedFile {
+        path: "crates/cli/src/main.rs".into(),
+        added: false,
+    };
+    let json = serde_json::to_string(&changed).unwrap();
+    assert_eq!(serde_json::from_str::<ChangedFile>(&json).unwrap(), changed);
+
+    let uncovered = UncoveredPackage {
+        package_name: "cli".to_string(),
+        files: vec![changed.path],
+    };
+    let json = serde_json::to_string(&uncovered).unwrap();
+    assert_eq!(
+        serde_json::from_str::<UncoveredPackage>(&json).unwrap(),
+        uncovered
+    );
+}
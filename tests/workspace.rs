@@ -0,0 +1,336 @@
+#![cfg(all(feature = "json", feature = "toml"))]
+
+use changesets::{ChangeSet, Dependency, Version, VersionReq, Workspace, WorkspaceError};
+use tempfile::tempdir;
+
+fn write(root: &std::path::Path, path: &str, contents: &str) {
+    let path = root.join(path);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, contents).unwrap();
+}
+
+#[test]
+fn cargo_workspace() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    write(
+        root,
+        "Cargo.toml",
+        r#"
+[package]
+name = "changesets"
+version = "0.4.0"
+
+[workspace]
+members = ["crates/*", "tools/cli"]
+exclude = ["crates/ignored"]
+
+[workspace.package]
+version = "1.2.3-beta+build.5"
+"#,
+    );
+    write(
+        root,
+        "crates/knope/Cargo.toml",
+        "[package]\nname = \"knope\"\nversion.workspace = true\n",
+    );
+    write(
+        root,
+        "crates/ignored/Cargo.toml",
+        "[package]\nname = \"ignored\"\n",
+    );
+    write(root, "crates/not_a_crate/README.md", "");
+    write(root, "tools/cli/Cargo.toml", "[package]\nname = \"cli\"\n");
+
+    let workspace = Workspace::from_cargo(root).unwrap();
+    let packages: Vec<(&str, Option<Version>)> = workspace
+        .packages
+        .iter()
+        .map(|package| (package.name.as_str(), package.version.clone()))
+        .collect();
+    assert_eq!(
+        packages,
+        vec![
+            ("changesets", Some(Version::new(0, 4, 0))),
+            ("knope", Some("1.2.3-beta+build.5".parse().unwrap())),
+            ("cli", None),
+        ]
+    );
+    assert_eq!(
+        workspace.package("knope").unwrap().path,
+        root.join("crates/knope")
+    );
+}
+
+fn dependencies(workspace: &Workspace, name: &str) -> Vec<(String, String)> {
+    workspace
+        .package(name)
+        .unwrap()
+        .dependencies
+        .iter()
+        .map(|dependency| (dependency.name.clone(), dependency.requirement.to_string()))
+        .collect()
+}
+
+#[test]
+fn cargo_dependencies() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    write(
+        root,
+        "Cargo.toml",
+        r#"
+[workspace]
+members = ["crates/*"]
+
+[workspace.dependencies]
+core = { path = "crates/core", version = "1.0.0" }
+"#,
+    );
+    write(
+        root,
+        "crates/core/Cargo.toml",
+        "[package]\nname = \"core\"\nversion = \"1.0.0\"\n",
+    );
+    write(
+        root,
+        "crates/cli/Cargo.toml",
+        r#"
+[package]
+name = "cli"
+
+[dependencies]
+core.workspace = true
+serde = "1.0"
+renamed = { package = "macros", path = "../macros" }
+
+[dev-dependencies]
+test-utils = { path = "../test-utils" }
+
+[target.'cfg(windows)'.dependencies]
+windows-support = { path = "../windows-support", version = "0.2" }
+"#,
+    );
+    write(
+        root,
+        "crates/windows-support/Cargo.toml",
+        "[package]\nname = \"windows-support\"\n",
+    );
+    write(
+        root,
+        "crates/macros/Cargo.toml",
+        "[package]\nname = \"macros\"\n",
+    );
+    write(
+        root,
+        "crates/test-utils/Cargo.toml",
+        "[package]\nname = \"test-utils\"\n",
+    );
+
+    let workspace = Workspace::from_cargo(root).unwrap();
+    assert_eq!(
+        dependencies(&workspace, "cli"),
+        vec![
+            ("core".to_string(), "1.0.0".to_string()),
+            ("macros".to_string(), "*".to_string()),
+            ("windows-support".to_string(), "0.2".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn npm_workspaces() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    write(
+        root,
+        "package.json",
+        r#"{"private": true, "workspaces": {"packages": ["packages/*", "!packages/scratch"]}}"#,
+    );
+    write(
+        root,
+        "packages/core/package.json",
+        r#"{"name": "@scope/core", "version": "1.2.3"}"#,
+    );
+    write(
+        root,
+        "packages/ui/package.json",
+        r#"{
+            "name": "@scope/ui",
+            "version": "0.1.0",
+            "dependencies": {"@scope/core": "1.2.3", "react": "^18.0.0"},
+            "devDependencies": {"@scope/test-utils": "*"}
+        }"#,
+    );
+    write(
+        root,
+        "packages/scratch/package.json",
+        r#"{"name": "scratch"}"#,
+    );
+    write(
+        root,
+        "packages/node_modules/dep/package.json",
+        r#"{"name": "dep"}"#,
+    );
+
+    let workspace = Workspace::from_javascript(root).unwrap();
+    let names: Vec<&str> = workspace
+        .packages
+        .iter()
+        .map(|package| package.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["@scope/core", "@scope/ui"]);
+    assert_eq!(
+        dependencies(&workspace, "@scope/ui"),
+        vec![("@scope/core".to_string(), "=1.2.3".to_string())]
+    );
+}
+
+#[test]
+fn npm_requirements() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    write(root, "package.json", r#"{"workspaces": ["packages/*"]}"#);
+    write(
+        root,
+        "packages/core/package.json",
+        r#"{"name": "core", "version": "1.2.3"}"#,
+    );
+    write(
+        root,
+        "packages/ui/package.json",
+        r#"{"name": "ui", "dependencies": {"core": "1.0.0 - 2.0.0"}}"#,
+    );
+    let workspace = Workspace::from_javascript(root).unwrap();
+    let ui = workspace.package("ui").unwrap();
+    assert!(
+        ui.dependencies[0]
+            .requirement
+            .matches(&Version::new(2, 0, 0))
+    );
+    assert!(
+        !ui.dependencies[0]
+            .requirement
+            .matches(&Version::new(2, 0, 1))
+    );
+
+    write(
+        root,
+        "packages/ui/package.json",
+        r#"{"name": "ui", "dependencies": {"core": "latest"}}"#,
+    );
+    let err = Workspace::from_javascript(root).unwrap_err();
+    assert!(matches!(err, WorkspaceError::InvalidManifest { .. }));
+    assert!(
+        err.to_string()
+            .contains("invalid requirement `latest` for `core`"),
+        "{err}"
+    );
+}
+
+#[test]
+fn pnpm_workspace_planning() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    write(root, "package.json", r#"{"name": "root", "private": true}"#);
+    write(
+        root,
+        "pnpm-workspace.yaml",
+        "packages:\n  - 'packages/*'\n  - '!**/test'\n",
+    );
+    write(
+        root,
+        "packages/core/package.json",
+        r#"{"name": "core", "version": "1.2.3"}"#,
+    );
+    write(
+        root,
+        "packages/app/package.json",
+        r#"{"name": "app", "version": "0.1.0", "dependencies": {"core": "workspace:^"}}"#,
+    );
+    write(
+        root,
+        "packages/test/package.json",
+        r#"{"name": "test", "dependencies": {"core": "workspace:*"}}"#,
+    );
+    write(
+        root,
+        ".changeset/breaking.md",
+        "---\ncore: major\n---\n\nA breaking change\n",
+    );
+
+    let workspace = Workspace::from_javascript(root).unwrap();
+    let planner = workspace.planner();
+    assert_eq!(
+        planner.dependencies.get("app"),
+        Some(&vec![Dependency {
+            name: "core".to_string(),
+            requirement: "^1.2.3".parse::<VersionReq>().unwrap(),
+        }])
+    );
+    assert!(!planner.dependencies.contains_key("test"));
+    assert_eq!(planner.versions.get("core"), Some(&Version::new(1, 2, 3)));
+
+    let changeset = ChangeSet::from_directory(root.join(".changeset")).unwrap();
+    let released: Vec<String> = planner
+        .plan(&changeset)
+        .into_iter()
+        .map(|release| release.package_name)
+        .collect();
+    assert_eq!(released, vec!["core", "app"]);
+}
+
+#[test]
+fn validate_changeset() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    write(
+        root,
+        "Cargo.toml",
+        "[workspace]\nmembers = [\"crates/*\"]\n",
+    );
+    write(
+        root,
+        "crates/changesets/Cargo.toml",
+        "[package]\nname = \"changesets\"\n",
+    );
+    write(
+        root,
+        "crates/knope/Cargo.toml",
+        "[package]\nname = \"knope\"\n",
+    );
+    write(
+        root,
+        ".changeset/typo.md",
+        "---\nchangeset: minor\nknope: patch\n---\n\nA feature\n",
+    );
+    write(
+        root,
+        ".changeset/unrelated.md",
+        "---\nsomething-else: patch\n---\n\nA fix\n",
+    );
+
+    let workspace = Workspace::from_cargo(root).unwrap();
+    let changeset = ChangeSet::from_directory(root.join(".changeset")).unwrap();
+    let unknown: Vec<String> = workspace
+        .validate(&changeset)
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(
+        unknown,
+        vec![
+            "unknown package `changeset` in typo.md, did you mean `changesets`?",
+            "unknown package `something-else` in unrelated.md",
+        ]
+    );
+}
+
+#[test]
+fn invalid_manifest() {
+    let dir = tempdir().unwrap();
+    write(dir.path(), "Cargo.toml", "[package]\nversion = \"1.0.0\"\n");
+    let err = Workspace::from_cargo(dir.path()).unwrap_err();
+    assert!(matches!(err, WorkspaceError::InvalidManifest { .. }));
+    assert!(err.to_string().ends_with("missing `package.name`"));
+}